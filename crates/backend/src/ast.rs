@@ -1,3 +1,7 @@
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
 use proc_macro2::{Ident, Span};
 use shared;
 use syn;
@@ -21,6 +25,19 @@ pub struct Program {
     pub consts: Vec<Const>,
     /// rust submodules
     pub modules: Vec<Module>,
+    /// snippets of JS written inline with `inline_js`
+    pub inline_js: Vec<InlineJs>,
+}
+
+/// A snippet of JS written inline in Rust source with
+/// `#[wasm_bindgen(inline_js = "...")]`, imported from as a module.
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub struct InlineJs {
+    /// The generated module name that imports of this snippet use.
+    pub identifier: String,
+    /// The JS source of the snippet.
+    pub contents: String,
 }
 
 /// A rust to js interface. Allows interaction with rust objects/functions
@@ -259,15 +276,89 @@ impl Program {
             exports: self.exports.iter().map(|a| a.shared()).collect(),
             structs: self.structs.iter().map(|a| a.shared()).collect(),
             enums: self.enums.iter().map(|a| a.shared()).collect(),
-            imports: self.imports.iter()
-                // add in imports from inside modules
-                .chain(self.modules.iter().flat_map(|m| m.imports.iter()))
+            imports: self.all_imports()
                 .map(|a| a.shared())
                 .collect::<Result<_, Diagnostic>>()?,
+            local_modules: self.shared_local_modules()?,
+            crate_name: env::var("CARGO_PKG_NAME")
+                .expect("should have CARGO_PKG_NAME env var"),
             version: shared::version(),
             schema_version: shared::SCHEMA_VERSION.to_string(),
         })
     }
+
+    /// All imports in this program, including those from inside modules.
+    pub(crate) fn all_imports<'a>(&'a self) -> impl Iterator<Item = &'a Import> + 'a {
+        self.imports.iter()
+            .chain(self.modules.iter().flat_map(|m| m.imports.iter()))
+    }
+
+    /// Paths on disk of all the JS files local to this crate which are
+    /// imported by this program, deduplicated.
+    ///
+    /// The paths are strings as they're passed to `include_str!`.
+    pub(crate) fn local_module_paths(&self) -> Result<Vec<(String, String)>, Diagnostic> {
+        let mut ret: Vec<(String, String)> = Vec::new();
+        for import in self.all_imports() {
+            let module = match import.module {
+                Some(ref m) => m,
+                None => continue,
+            };
+            if !shared::is_local_module(module) || ret.iter().any(|p| p.0 == *module) {
+                continue;
+            }
+            // Local modules must stay inside the crate, and are written out
+            // below the output directory later on.
+            let escapes = Path::new(&module[1..]).components().any(|c| match c {
+                Component::Normal(_) | Component::CurDir => false,
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => true,
+            });
+            if escapes {
+                return Err(Diagnostic::error(format!(
+                    "local JS module `{}` must not leave the crate's directory",
+                    module
+                )));
+            }
+            let dir = env::var_os("CARGO_MANIFEST_DIR")
+                .ok_or_else(|| Diagnostic::error("should have CARGO_MANIFEST_DIR env var"))?;
+            let path = PathBuf::from(dir).join(&module[1..]);
+            let path = match path.to_str() {
+                Some(path) => path.to_string(),
+                None => {
+                    return Err(Diagnostic::error(format!(
+                        "path of local JS module `{}` is not valid UTF-8: {}",
+                        module,
+                        path.display()
+                    )))
+                }
+            };
+            ret.push((module.clone(), path));
+        }
+        Ok(ret)
+    }
+
+    fn shared_local_modules(&self) -> Result<Vec<shared::LocalModule>, Diagnostic> {
+        let inline = self.inline_js.iter().map(|js| {
+            Ok(shared::LocalModule {
+                identifier: js.identifier.clone(),
+                contents: js.contents.clone(),
+            })
+        });
+        self.local_module_paths()?
+            .into_iter()
+            .map(|(identifier, path)| -> Result<_, Diagnostic> {
+                let contents = fs::read_to_string(&path).map_err(|e| {
+                    Diagnostic::error(format!(
+                        "failed to read local JS module `{}`: {}",
+                        path,
+                        e
+                    ))
+                })?;
+                Ok(shared::LocalModule { identifier, contents })
+            })
+            .chain(inline)
+            .collect()
+    }
 }
 
 impl Function {
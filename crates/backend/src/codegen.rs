@@ -114,6 +114,26 @@ impl TryToTokens for ast::Program {
                 *#generated_static_value;
         }).to_tokens(tokens);
 
+        // Local JS modules are read by the macro and embedded in the custom
+        // section above, so also `include_str!` them to make sure this crate
+        // is rebuilt whenever one of them changes.
+        let local_modules = self.local_module_paths()?;
+        if local_modules.len() > 0 {
+            let generated_includes_name = Ident::new(
+                &format!("{}_LOCAL_MODULES", generated_static_name),
+                Span::call_site(),
+            );
+            let paths = local_modules.iter().map(|&(_, ref path)| path);
+            let count = local_modules.len();
+            (quote! {
+                #[allow(dead_code, non_upper_case_globals)]
+                #[doc(hidden)]
+                const #generated_includes_name: [&str; #count] = [
+                    #(include_str!(#paths),)*
+                ];
+            }).to_tokens(tokens);
+        }
+
         Ok(())
     }
 }
@@ -422,14 +442,31 @@ impl TryToTokens for ast::Export {
         }
         let ret_ty;
         let convert_ret;
-        match &self.function.ret {
-            Some(syn::Type::Reference(_)) => {
+        let future_item = self.function.ret.as_ref().and_then(future_item);
+        match (&self.function.ret, future_item) {
+            // Functions returning futures are exported as returning a
+            // `Promise`, which needs the `wasm-bindgen-futures` crate.
+            (_, Some(_)) => {
+                ret_ty = quote! {
+                    -> <::wasm_bindgen::JsValue as ::wasm_bindgen::convert::IntoWasmAbi>::Abi
+                };
+                convert_ret = quote! {
+                    <::wasm_bindgen::JsValue as ::wasm_bindgen::convert::IntoWasmAbi>
+                        ::into_abi(
+                            ::wasm_bindgen_futures::__rt::future_to_promise(#ret),
+                            &mut unsafe {
+                                ::wasm_bindgen::convert::GlobalStack::new()
+                            },
+                        )
+                };
+            }
+            (Some(syn::Type::Reference(_)), None) => {
                 bail_span!(
                     self.function.ret,
                     "cannot return a borrowed ref with #[wasm_bindgen]",
                 )
             }
-            Some(ty) => {
+            (Some(ty), None) => {
                 ret_ty = quote! {
                     -> <#ty as ::wasm_bindgen::convert::IntoWasmAbi>::Abi
                 };
@@ -440,19 +477,26 @@ impl TryToTokens for ast::Export {
                         })
                 };
             }
-            None => {
+            (None, None) => {
                 ret_ty = quote!();
                 convert_ret = quote!();
             }
         }
-        let describe_ret = match &self.function.ret {
-            Some(ty) => {
+        let describe_ret = match (&self.function.ret, future_item) {
+            (_, Some(item)) => {
+                quote! {
+                    inform(1);
+                    inform(PROMISE);
+                    <#item as WasmDescribe>::describe();
+                }
+            }
+            (Some(ty), None) => {
                 quote! {
                     inform(1);
                     <#ty as WasmDescribe>::describe();
                 }
             }
-            None => quote! { inform(0); },
+            (None, None) => quote! { inform(0); },
         };
         let nargs = self.function.arguments.len() as u32;
         let argtys = self.function.arguments.iter().map(|arg| &arg.ty);
@@ -505,6 +549,41 @@ impl TryToTokens for ast::Export {
     }
 }
 
+/// Returns the `Item` of `ty` if it's of the form
+/// `impl Future<Item = T, Error = E>`.
+fn future_item(ty: &syn::Type) -> Option<&syn::Type> {
+    let bounds = match ty {
+        syn::Type::ImplTrait(t) => &t.bounds,
+        _ => return None,
+    };
+    for bound in bounds {
+        let path = match bound {
+            syn::TypeParamBound::Trait(t) => &t.path,
+            _ => continue,
+        };
+        let segment = match path.segments.last() {
+            Some(s) => s.into_value(),
+            None => continue,
+        };
+        if segment.ident != "Future" {
+            continue
+        }
+        let args = match &segment.arguments {
+            syn::PathArguments::AngleBracketed(a) => &a.args,
+            _ => continue,
+        };
+        for arg in args {
+            match arg {
+                syn::GenericArgument::Binding(b) if b.ident == "Item" => {
+                    return Some(&b.ty)
+                }
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
 impl TryToTokens for ast::ImportKind {
     fn try_to_tokens(&self, tokens: &mut TokenStream) -> Result<(), Diagnostic> {
         match *self {
@@ -34,6 +34,7 @@ tys! {
     RUST_STRUCT
     CHAR
     OPTIONAL
+    PROMISE
 }
 
 #[derive(Debug)]
@@ -61,6 +62,7 @@ pub enum Descriptor {
     RustStruct(String),
     Char,
     Option(Box<Descriptor>),
+    Promise(Box<Descriptor>),
 }
 
 #[derive(Debug)]
@@ -118,6 +120,7 @@ impl Descriptor {
             SLICE => Descriptor::Slice(Box::new(Descriptor::_decode(data))),
             VECTOR => Descriptor::Vector(Box::new(Descriptor::_decode(data))),
             OPTIONAL => Descriptor::Option(Box::new(Descriptor::_decode(data))),
+            PROMISE => Descriptor::Promise(Box::new(Descriptor::_decode(data))),
             STRING => Descriptor::String,
             ANYREF => Descriptor::Anyref,
             ENUM => Descriptor::Enum,
@@ -210,6 +213,13 @@ impl Descriptor {
         }
     }
 
+    pub fn promise(&self) -> Option<&Descriptor> {
+        match *self {
+            Descriptor::Promise(ref d) => Some(d),
+            _ => None,
+        }
+    }
+
     pub fn vector_kind(&self) -> Option<VectorKind> {
         let inner = match *self {
             Descriptor::String => return Some(VectorKind::String),
@@ -400,6 +400,15 @@ impl<'a, 'b> Js2Rust<'a, 'b> {
             }
         };
 
+        // Exported functions returning futures are converted to a `Promise`
+        // on the Rust side, so here it's just an object like any other.
+        if let Some(item) = ty.promise() {
+            self.ret_ty = format!("Promise<{}>", promise_item_ty(item));
+            self.cx.expose_take_object();
+            self.ret_expr = format!("return takeObject(RET);");
+            return Ok(self);
+        }
+
         let (ty, optional) = match ty {
             Descriptor::Option(t) => (&**t, true),
             _ => (ty, false),
@@ -658,3 +667,40 @@ impl<'a, 'b> Js2Rust<'a, 'b> {
         (js, ts, self.js_doc_comments())
     }
 }
+
+/// Returns the TypeScript type of values a `Promise` returned from Rust
+/// resolves to, given the descriptor of the future's `Item`.
+fn promise_item_ty(ty: &Descriptor) -> String {
+    match ty {
+        Descriptor::Option(t) => optional_ty(promise_item_ty(t)),
+        _ => promise_value_ty(ty),
+    }
+}
+
+/// Like `promise_item_ty`, for items which aren't an `Option`.
+fn promise_value_ty(ty: &Descriptor) -> String {
+    if let Some(kind) = ty.vector_kind() {
+        return kind.js_ty().to_string();
+    }
+    if let Some(name) = ty.rust_struct() {
+        return name.to_string();
+    }
+    if ty.is_number() {
+        return "number".to_string();
+    }
+    match *ty {
+        Descriptor::Boolean => "boolean".to_string(),
+        Descriptor::Char => "string".to_string(),
+        _ => "any".to_string(),
+    }
+}
+
+/// The TypeScript type of an `Option` of a value of type `ty`.
+fn optional_ty(ty: String) -> String {
+    // `undefined` is already included in `any`.
+    if ty == "any" {
+        ty
+    } else {
+        format!("{} | undefined", ty)
+    }
+}
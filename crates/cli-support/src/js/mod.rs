@@ -1,5 +1,6 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write;
+use std::io;
 use std::mem;
 
 use failure::{Error, ResultExt};
@@ -40,6 +41,11 @@ pub struct Context<'a> {
     pub imported_identifiers: HashMap<String, usize>,
 
     pub exported_classes: HashMap<String, ExportedClass>,
+
+    /// The contents of all local JS modules imported by the program, keyed by
+    /// the path relative to the output directory they're written to.
+    pub snippets: BTreeMap<String, String>,
+
     pub function_table_needed: bool,
     pub run_descriptor: &'a Fn(&str) -> Option<Vec<u32>>,
 }
@@ -114,7 +120,11 @@ impl<'a> Context<'a> {
         );
     }
 
-    pub fn finalize(&mut self, module_name: &str) -> Result<(String, String), Error> {
+    pub fn finalize(
+        &mut self,
+        module_name: &str,
+        wasm_import_path: &str,
+    ) -> Result<(String, String), Error> {
         self.write_classes()?;
 
         self.bind("__wbindgen_object_clone_ref", &|me| {
@@ -433,10 +443,10 @@ impl<'a> Context<'a> {
                 String::new()
             } else if self.use_node_require() {
                 self.footer
-                    .push_str(&format!("wasm = require('./{}_bg');", module_name));
+                    .push_str(&format!("wasm = require('{}');", wasm_import_path));
                 format!("var wasm;")
             } else {
-                format!("import * as wasm from './{}_bg';", module_name)
+                format!("import * as wasm from '{}';", wasm_import_path)
             };
 
             format!(
@@ -1595,8 +1605,29 @@ impl<'a> Context<'a> {
     }
 
     fn gc(&mut self) -> Result<(), Error> {
-        let module = mem::replace(self.module, Module::default());
-        let module = module.parse_names().unwrap_or_else(|p| p.1);
+        let mut module = mem::replace(self.module, Module::default());
+
+        // The `wasm_gc` pass below removes and renumbers functions, which
+        // means that it also needs to rewrite the `name` section so function
+        // names in profiles and stack traces still line up. It can only do
+        // that for name sections that `parity_wasm` has parsed, and
+        // `parity_wasm` only understands one subsection per `name` section,
+        // silently dropping the rest. To work around that we split the name
+        // section into one section per subsection here and stitch them back
+        // together once the gc pass is done.
+        split_name_section(&mut module);
+        let module = match module.parse_names() {
+            Ok(m) => m,
+            // Any subsection we failed to parse can't be rewritten, and
+            // would be referring to the wrong functions after gc, so drop it
+            // entirely rather than emit stale names.
+            Err((errors, mut m)) => {
+                for (i, _) in errors.into_iter().rev() {
+                    m.sections_mut().remove(i);
+                }
+                m
+            }
+        };
         let result = wasm_gc::Config::new()
             .demangle(self.config.demangle)
             .keep_debug(self.config.keep_debug || self.config.debug)
@@ -1605,6 +1636,7 @@ impl<'a> Context<'a> {
             Ok(m) => m,
             Err(result) => deserialize_buffer(&result.into_bytes()?)?,
         };
+        merge_name_sections(self.module)?;
         Ok(())
     }
 
@@ -1647,6 +1679,10 @@ impl<'a, 'b> SubContext<'a, 'b> {
         for e in self.program.enums.iter() {
             self.generate_enum(e);
         }
+        for m in self.program.local_modules.iter() {
+            let path = shared::local_module_path(&self.program.crate_name, &m.identifier);
+            self.cx.snippets.insert(path, m.contents.clone());
+        }
         for s in self.program.structs.iter() {
             let mut class = self
                 .cx
@@ -2011,6 +2047,20 @@ impl<'a, 'b> SubContext<'a, 'b> {
             .map(|s| &**s)
             .unwrap_or(item);
 
+        // Local JS modules and inline JS snippets are written out alongside
+        // the generated JS, so rewrite their specifiers to point there.
+        let local_modules = &self.program.local_modules;
+        let module = import.module.as_ref().map(|module| {
+            if local_modules.iter().any(|m| m.identifier == *module) {
+                format!(
+                    "./{}",
+                    shared::local_module_path(&self.program.crate_name, module)
+                )
+            } else {
+                module.clone()
+            }
+        });
+
         // Here's where it's a bit tricky. We need to make sure that importing
         // the same identifier from two different modules works, and they're
         // named uniquely below. Additionally if we've already imported the same
@@ -2025,12 +2075,12 @@ impl<'a, 'b> SubContext<'a, 'b> {
         let use_node_require = self.cx.use_node_require();
         let imported_identifiers = &mut self.cx.imported_identifiers;
         let imports = &mut self.cx.imports;
-        let identifier = self.cx.imported_names.entry(import.module.clone())
+        let identifier = self.cx.imported_names.entry(module.clone())
             .or_insert_with(Default::default)
             .entry(name_to_import.to_string())
             .or_insert_with(|| {
                 let name = generate_identifier(name_to_import, imported_identifiers);
-                if let Some(module) = &import.module {
+                if let Some(module) = &module {
                     if use_node_require {
                         imports.push_str(&format!(
                             "const {} = require('{}').{};\n",
@@ -2083,3 +2133,101 @@ fn format_doc_comments(comments: &Vec<String>, js_doc_comments: Option<String>)
     };
     format!("/**\n{}{}*/\n", body, doc)
 }
+
+/// Splits every `name` custom section in `module` into one custom section per
+/// subsection, so each can be individually parsed by `Module::parse_names`.
+///
+/// Sections which are malformed are removed entirely.
+fn split_name_section(module: &mut Module) {
+    let sections = module.sections_mut();
+    let mut i = 0;
+    while i < sections.len() {
+        let subsections = match sections[i] {
+            Section::Custom(ref s) if s.name() == "name" => name_subsections(s.payload()),
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        let subsections = subsections
+            .unwrap_or_default()
+            .into_iter()
+            .map(|payload| {
+                let mut custom = CustomSection::default();
+                *custom.name_mut() = "name".to_string();
+                *custom.payload_mut() = payload;
+                Section::Custom(custom)
+            })
+            .collect::<Vec<_>>();
+        let n = subsections.len();
+        sections.splice(i..i + 1, subsections);
+        i += n;
+    }
+}
+
+/// Returns the raw bytes (id, size and contents) of each subsection in the
+/// payload of a `name` section, or `None` if it's malformed.
+fn name_subsections(payload: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut ret = Vec::new();
+    let mut rdr = io::Cursor::new(payload);
+    while (rdr.position() as usize) < payload.len() {
+        let start = rdr.position() as usize;
+        VarUint7::deserialize(&mut rdr).ok()?;
+        let len: u32 = VarUint32::deserialize(&mut rdr).ok()?.into();
+        let end = rdr.position() as usize + len as usize;
+        if end > payload.len() {
+            return None;
+        }
+        rdr.set_position(end as u64);
+        ret.push(payload[start..end].to_vec());
+    }
+    Some(ret)
+}
+
+/// The inverse of `split_name_section`, merges all name sections back into
+/// one `name` custom section with subsections ordered by their id, as
+/// required by the spec.
+///
+/// The sections may either have been parsed, or still be custom sections if
+/// the module was deserialized again after gc.
+fn merge_name_sections(module: &mut Module) -> Result<(), Error> {
+    fn is_name_section(section: &Section) -> bool {
+        match *section {
+            Section::Name(_) => true,
+            Section::Custom(ref custom) => custom.name() == "name",
+            _ => false,
+        }
+    }
+
+    let sections = module.sections_mut();
+    let first = match sections.iter().position(is_name_section) {
+        Some(i) => i,
+        None => return Ok(()),
+    };
+    let mut subsections = Vec::new();
+    let mut i = first;
+    while i < sections.len() {
+        if !is_name_section(&sections[i]) {
+            i += 1;
+            continue;
+        }
+        match sections.remove(i) {
+            Section::Name(name) => subsections.push(parity_wasm::serialize(name)?),
+            Section::Custom(custom) => {
+                if !custom.payload().is_empty() {
+                    subsections.push(custom.payload().to_vec());
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    // Each serialized subsection starts with its one-byte id, and the sort
+    // is stable so duplicates stay in their original order.
+    subsections.sort_by_key(|s| s[0]);
+    let mut custom = CustomSection::default();
+    *custom.name_mut() = "name".to_string();
+    *custom.payload_mut() = subsections.concat();
+    sections.insert(first, Section::Custom(custom));
+    Ok(())
+}
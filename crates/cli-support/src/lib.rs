@@ -13,7 +13,7 @@ use std::collections::BTreeSet;
 use std::fmt;
 use std::fs;
 use std::mem;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 use failure::{Error, ResultExt};
 use parity_wasm::elements::*;
@@ -33,6 +33,9 @@ pub struct Bindgen {
     typescript: bool,
     demangle: bool,
     keep_debug: bool,
+    out_name: Option<String>,
+    wasm_name: Option<String>,
+    wasm_import_path: Option<String>,
 }
 
 enum Input {
@@ -55,6 +58,9 @@ impl Bindgen {
             typescript: false,
             demangle: true,
             keep_debug: false,
+            out_name: None,
+            wasm_name: None,
+            wasm_import_path: None,
         }
     }
 
@@ -138,11 +144,40 @@ impl Bindgen {
         self
     }
 
+    /// Configures the base name of the generated JS and TypeScript files,
+    /// which otherwise defaults to the name of the input file.
+    pub fn out_name(&mut self, name: &str) -> &mut Bindgen {
+        self.out_name = Some(name.to_string());
+        self
+    }
+
+    /// Configures the name of the generated wasm file, without the `.wasm`
+    /// extension, which otherwise defaults to `<out_name>_bg`.
+    pub fn wasm_name(&mut self, name: &str) -> &mut Bindgen {
+        self.wasm_name = Some(name.to_string());
+        self
+    }
+
+    /// Configures the module specifier the generated JS uses to import the
+    /// wasm file, which otherwise defaults to `./<wasm_name>`.
+    ///
+    /// This can be used to point at a CDN URL or a file name produced by an
+    /// asset pipeline. It's not supported with `no_modules`, where the path to
+    /// the wasm file is passed at runtime instead.
+    pub fn wasm_import_path(&mut self, path: &str) -> &mut Bindgen {
+        self.wasm_import_path = Some(path.to_string());
+        self
+    }
+
     pub fn generate<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
-        self._generate(path.as_ref())
+        self.generate_output()?.emit(path)
     }
 
-    fn _generate(&mut self, out_dir: &Path) -> Result<(), Error> {
+    /// Generates bindings like `generate`, but returns all of the generated
+    /// files in memory rather than writing them out to a directory.
+    ///
+    /// The returned `Output` can later be written to disk with `Output::emit`.
+    pub fn generate_output(&mut self) -> Result<Output, Error> {
         let (mut module, stem) = match self.input {
             Input::None => bail!("must have an input by now"),
             Input::Module(ref mut m, ref name) => {
@@ -163,6 +198,18 @@ impl Bindgen {
                 (module, stem)
             }
         };
+        if self.no_modules && self.wasm_import_path.is_some() {
+            bail!("a custom wasm import path is not supported with `--no-modules`");
+        }
+        let stem = self.out_name.as_ref().map(|s| &s[..]).unwrap_or(stem);
+        let wasm_name = match self.wasm_name {
+            Some(ref name) => name.clone(),
+            None => format!("{}_bg", stem),
+        };
+        let wasm_import_path = match self.wasm_import_path {
+            Some(ref path) => path.clone(),
+            None => format!("./{}", wasm_name),
+        };
         let programs = extract_programs(&mut module)
             .with_context(|_| "failed to extract wasm-bindgen custom sections")?;
 
@@ -187,7 +234,7 @@ impl Bindgen {
             .with_context(|_| "failed to instantiate wasm module")?;
         let instance = instance.not_started_instance();
 
-        let (js, ts) = {
+        let (js, ts, snippets) = {
             let mut cx = js::Context {
                 globals: String::new(),
                 imports: String::new(),
@@ -198,6 +245,7 @@ impl Bindgen {
                 imported_names: Default::default(),
                 imported_identifiers: Default::default(),
                 exported_classes: Default::default(),
+                snippets: Default::default(),
                 config: &self,
                 module: &mut module,
                 function_table_needed: false,
@@ -222,33 +270,29 @@ impl Bindgen {
                     cx: &mut cx,
                 }.generate()?;
             }
-            cx.finalize(stem)?
+            let (js, ts) = cx.finalize(stem, &wasm_import_path)?;
+            (js, ts, cx.snippets)
         };
 
         let extension = if self.nodejs_experimental_modules { "mjs" } else { "js" };
-        let js_path = out_dir.join(stem).with_extension(extension);
-        fs::write(&js_path, reset_indentation(&js))
-            .with_context(|_| format!("failed to write `{}`", js_path.display()))?;
-
-        if self.typescript {
-            let ts_path = out_dir.join(stem).with_extension("d.ts");
-            fs::write(&ts_path, ts)
-                .with_context(|_| format!("failed to write `{}`", ts_path.display()))?;
-        }
-
-        let wasm_path = out_dir.join(format!("{}_bg", stem)).with_extension("wasm");
+        let wasm_file = format!("{}.wasm", wasm_name);
 
+        let mut extra_files = Vec::new();
         if self.nodejs {
-            let js_path = wasm_path.with_extension(extension);
-            let shim = self.generate_node_wasm_import(&module, &wasm_path);
-            fs::write(&js_path, shim)
-                .with_context(|_| format!("failed to write `{}`", js_path.display()))?;
+            let shim = self.generate_node_wasm_import(&module, Path::new(&wasm_file));
+            extra_files.push((format!("{}.{}", wasm_name, extension), shim));
         }
-
-        let wasm_bytes = parity_wasm::serialize(module)?;
-        fs::write(&wasm_path, wasm_bytes)
-            .with_context(|_| format!("failed to write `{}`", wasm_path.display()))?;
-        Ok(())
+        extra_files.extend(snippets);
+
+        Ok(Output {
+            js_name: format!("{}.{}", stem, extension),
+            js: reset_indentation(&js),
+            typescript_name: format!("{}.d.ts", stem),
+            typescript: if self.typescript { Some(ts) } else { None },
+            wasm_name: wasm_file,
+            wasm: parity_wasm::serialize(module)?,
+            extra_files,
+        })
     }
 
     fn generate_node_wasm_import(&self, m: &Module, path: &Path) -> String {
@@ -320,6 +364,96 @@ impl Bindgen {
     }
 }
 
+/// All of the files generated by `Bindgen::generate_output`, held in memory.
+pub struct Output {
+    js_name: String,
+    js: String,
+    typescript_name: String,
+    typescript: Option<String>,
+    wasm_name: String,
+    wasm: Vec<u8>,
+    extra_files: Vec<(String, String)>,
+}
+
+impl Output {
+    /// Returns the generated JS glue.
+    pub fn js(&self) -> &str {
+        &self.js
+    }
+
+    /// Returns the file name, relative to the output directory, that the JS
+    /// glue is written to.
+    pub fn js_name(&self) -> &str {
+        &self.js_name
+    }
+
+    /// Returns the generated TypeScript definitions, if `typescript` was
+    /// enabled.
+    pub fn typescript(&self) -> Option<&str> {
+        self.typescript.as_ref().map(|s| &**s)
+    }
+
+    /// Returns the file name, relative to the output directory, that the
+    /// TypeScript definitions are written to.
+    pub fn typescript_name(&self) -> &str {
+        &self.typescript_name
+    }
+
+    /// Returns the final wasm module, which the JS glue imports.
+    pub fn wasm(&self) -> &[u8] {
+        &self.wasm
+    }
+
+    /// Returns the file name, relative to the output directory, that the wasm
+    /// module is written to.
+    pub fn wasm_name(&self) -> &str {
+        &self.wasm_name
+    }
+
+    /// Returns any other generated JS files as pairs of their file name,
+    /// relative to the output directory, and their contents.
+    pub fn extra_files(&self) -> &[(String, String)] {
+        &self.extra_files
+    }
+
+    /// Writes all generated files into the `out_dir` directory.
+    pub fn emit<P: AsRef<Path>>(&self, out_dir: P) -> Result<(), Error> {
+        self._emit(out_dir.as_ref())
+    }
+
+    fn _emit(&self, out_dir: &Path) -> Result<(), Error> {
+        let write = |name: &str, contents: &[u8]| -> Result<(), Error> {
+            // Names come from the wasm module, so make sure nothing gets
+            // written outside of `out_dir`.
+            let stays_inside = Path::new(name).components().all(|c| match c {
+                Component::Normal(_) | Component::CurDir => true,
+                _ => false,
+            });
+            if !stays_inside {
+                bail!("refusing to write `{}` outside of the output directory", name);
+            }
+            let path = out_dir.join(name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|_| format!("failed to create `{}`", parent.display()))?;
+            }
+            fs::write(&path, contents)
+                .with_context(|_| format!("failed to write `{}`", path.display()))?;
+            Ok(())
+        };
+
+        write(&self.js_name, self.js.as_bytes())?;
+        if let Some(ref ts) = self.typescript {
+            write(&self.typescript_name, ts.as_bytes())?;
+        }
+        for &(ref name, ref contents) in self.extra_files.iter() {
+            write(name, contents.as_bytes())?;
+        }
+        write(&self.wasm_name, &self.wasm)?;
+        Ok(())
+    }
+}
+
 fn extract_programs(module: &mut Module) -> Result<Vec<shared::Program>, Error> {
     let version = shared::version();
     let mut ret = Vec::new();
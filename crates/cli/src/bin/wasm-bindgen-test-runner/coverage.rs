@@ -0,0 +1,640 @@
+//! Line and function coverage of wasm tests.
+//!
+//! With `--coverage` the wasm module is instrumented before `wasm-bindgen`
+//! runs over it: every function increments a counter on entry, as does every
+//! block, loop and branch of an `if`. The counters live in linear memory just
+//! past the module's initial memory, which Rust's allocator never hands out as
+//! it only uses memory it grows itself.
+//!
+//! Counters are mapped back to Rust source files and lines through the DWARF
+//! line table in the `.debug_line` custom section, so the wasm file must be
+//! built with debug info. Once tests have finished the generated JS reads the
+//! counters and renders an lcov file from them.
+
+use std::collections::HashMap;
+use std::mem;
+
+use failure::Error;
+use parity_wasm::elements::{External, ImportCountType, Instruction, MemoryType};
+use parity_wasm::elements::{Module, NameSection, Section};
+use rustc_demangle;
+
+/// Size of a wasm page, in bytes.
+const PAGE_SIZE: u32 = 64 * 1024;
+
+/// Description of the counters inserted by `instrument`.
+pub struct Coverage {
+    /// Address in linear memory of the first counter.
+    offset: u32,
+    /// Number of counters.
+    counters: usize,
+    /// Source files the counters are attributed to.
+    files: Vec<String>,
+    /// File, line, demangled name and entry counter of each function.
+    functions: Vec<(usize, u32, String, usize)>,
+    /// File, line and counter of each instrumented location.
+    lines: Vec<(usize, u32, usize)>,
+}
+
+/// Instruments `module`, deserialized from `bytes`, to count how often each
+/// function and block in it runs, returning where the counters are stored
+/// and which source lines they belong to.
+///
+/// Functions without line information, like those from crates built without
+/// debug info, aren't instrumented.
+pub fn instrument(module: &mut Module, bytes: &[u8]) -> Result<Coverage, Error> {
+    let imported_memory = module.import_section()
+        .map(|s| s.entries().iter().any(|i| match i.external() {
+            External::Memory(_) => true,
+            _ => false,
+        }))
+        .unwrap_or(false);
+    if imported_memory {
+        bail!("coverage isn't supported for modules which import their memory");
+    }
+
+    let debug_line = module.sections().iter()
+        .filter_map(|s| match s {
+            Section::Custom(s) if s.name() == ".debug_line" => Some(s.payload()),
+            _ => None,
+        })
+        .next();
+    let lines = match debug_line {
+        Some(payload) => LineTable::parse(payload)?,
+        None => bail!("coverage requires debug info in the wasm file, but it \
+                       has no `.debug_line` section"),
+    };
+    let offsets = instruction_offsets(bytes)?;
+
+    let names = function_names(module);
+    let imported = module.import_count(ImportCountType::Function) as u32;
+    let mut coverage = Coverage {
+        offset: 0,
+        counters: 0,
+        files: lines.files.clone(),
+        functions: Vec::new(),
+        lines: Vec::new(),
+    };
+
+    // Figure out where each counter goes before touching the module, as
+    // we don't know where in memory the counters live until we've counted
+    // them.
+    let mut plans = Vec::new();
+    if let Some(code) = module.code_section() {
+        if code.bodies().len() != offsets.len() {
+            bail!("failed to find all function bodies in the wasm file");
+        }
+        for (i, (body, offsets)) in code.bodies().iter().zip(&offsets).enumerate() {
+            let instructions = body.code().elements();
+            if instructions.len() != offsets.len() {
+                bail!("failed to decode the instructions of function {}", imported + i as u32);
+            }
+            plans.push(coverage.plan(&lines, instructions, offsets, || {
+                match names.get(&(imported + i as u32)) {
+                    Some(name) => format!("{:#}", rustc_demangle::demangle(name)),
+                    None => format!("func[{}]", imported + i as u32),
+                }
+            }));
+        }
+    }
+
+    // Reserve enough pages past the initial memory for all the counters.
+    coverage.offset = {
+        let memory = match module.memory_section_mut().and_then(|s| s.entries_mut().first_mut()) {
+            Some(memory) => memory,
+            None => bail!("coverage requires the module to have a memory"),
+        };
+        let initial = memory.limits().initial();
+        let pages = ((coverage.counters as u32) * 4 + PAGE_SIZE - 1) / PAGE_SIZE;
+        let maximum = memory.limits().maximum();
+        if let Some(max) = maximum {
+            if max < initial + pages {
+                bail!("not enough memory to store coverage counters");
+            }
+        }
+        *memory = MemoryType::new(initial + pages, maximum);
+        initial * PAGE_SIZE
+    };
+
+    // And finally increment each counter at its location.
+    if let Some(code) = module.code_section_mut() {
+        for (body, plan) in code.bodies_mut().iter_mut().zip(plans) {
+            let instructions = body.code_mut().elements_mut();
+            let original = mem::replace(instructions, Vec::new());
+            if let Some(counter) = plan.entry {
+                instructions.extend(increment(coverage.offset, counter));
+            }
+            let mut blocks = plan.blocks.into_iter().peekable();
+            for (i, instruction) in original.into_iter().enumerate() {
+                instructions.push(instruction);
+                if blocks.peek().map(|b| b.0) == Some(i) {
+                    let counter = blocks.next().unwrap().1;
+                    instructions.extend(increment(coverage.offset, counter));
+                }
+            }
+        }
+    }
+
+    Ok(coverage)
+}
+
+/// Where to increment counters within one function.
+struct Plan {
+    /// The counter incremented on entry, if the function is instrumented.
+    entry: Option<usize>,
+    /// Indices of the instructions after which to increment a counter, and
+    /// which counter to increment, in order.
+    blocks: Vec<(usize, usize)>,
+}
+
+impl Coverage {
+    /// Allocates the counters for a function with the given `instructions`,
+    /// which start at `offsets` in the code section.
+    fn plan<F>(
+        &mut self,
+        lines: &LineTable,
+        instructions: &[Instruction],
+        offsets: &[u32],
+        name: F,
+    ) -> Plan
+        where F: FnOnce() -> String,
+    {
+        let mut plan = Plan { entry: None, blocks: Vec::new() };
+        let (file, line) = match offsets.iter().filter_map(|o| lines.find(*o)).next() {
+            Some(location) => location,
+            None => return plan,
+        };
+
+        let entry = self.counter(file, line);
+        self.functions.push((file, line, name(), entry));
+        plan.entry = Some(entry);
+
+        for (i, instruction) in instructions.iter().enumerate() {
+            match instruction {
+                Instruction::Block(_) |
+                Instruction::Loop(_) |
+                Instruction::If(_) |
+                Instruction::Else => {}
+                _ => continue,
+            }
+            let location = offsets.get(i + 1).and_then(|o| lines.find(*o));
+            if let Some((file, line)) = location {
+                let counter = self.counter(file, line);
+                plan.blocks.push((i, counter));
+            }
+        }
+        plan
+    }
+
+    fn counter(&mut self, file: usize, line: u32) -> usize {
+        let counter = self.counters;
+        self.counters += 1;
+        self.lines.push((file, line, counter));
+        counter
+    }
+
+    /// Returns the definition of a JS function `__wbg_test_coverage` which
+    /// takes the wasm module's memory and returns the lcov report.
+    pub fn js(&self) -> String {
+        let files = self.files.iter()
+            .map(|f| js_string(f))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let functions = self.functions.iter()
+            .map(|(file, line, name, counter)| {
+                format!("[{}, {}, {}, {}]", file, line, js_string(name), counter)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let lines = self.lines.iter()
+            .map(|(file, line, counter)| format!("[{}, {}, {}]", file, line, counter))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            r#"
+            function __wbg_test_coverage(memory) {{
+                const files = [{files}];
+                const functions = [{functions}];
+                const lines = [{lines}];
+                const counts = new Uint32Array(memory.buffer, {offset}, {counters});
+                let lcov = '';
+                files.forEach((file, f) => {{
+                    const fns = functions.filter(func => func[0] === f);
+                    const hits = new Map();
+                    for (const [lf, line, counter] of lines) {{
+                        if (lf === f)
+                            hits.set(line, Math.max(hits.get(line) || 0, counts[counter]));
+                    }}
+                    if (hits.size === 0)
+                        return;
+                    lcov += `TN:\nSF:${{file}}\n`;
+                    for (const [, line, name] of fns)
+                        lcov += `FN:${{line}},${{name}}\n`;
+                    for (const [, , name, counter] of fns)
+                        lcov += `FNDA:${{counts[counter]}},${{name}}\n`;
+                    const fnh = fns.filter(func => counts[func[3]] > 0).length;
+                    lcov += `FNF:${{fns.length}}\nFNH:${{fnh}}\n`;
+                    let lh = 0;
+                    for (const [line, count] of [...hits].sort((a, b) => a[0] - b[0])) {{
+                        lcov += `DA:${{line}},${{count}}\n`;
+                        if (count > 0)
+                            lh += 1;
+                    }}
+                    lcov += `LF:${{hits.size}}\nLH:${{lh}}\nend_of_record\n`;
+                }});
+                return lcov;
+            }}
+            "#,
+            files = files,
+            functions = functions,
+            lines = lines,
+            offset = self.offset,
+            counters = self.counters,
+        )
+    }
+}
+
+/// Instructions incrementing the counter `counter`.
+fn increment(offset: u32, counter: usize) -> Vec<Instruction> {
+    let addr = (offset + (counter as u32) * 4) as i32;
+    vec![
+        Instruction::I32Const(addr),
+        Instruction::I32Const(addr),
+        Instruction::I32Load(2, 0),
+        Instruction::I32Const(1),
+        Instruction::I32Add,
+        Instruction::I32Store(2, 0),
+    ]
+}
+
+/// Returns the names of functions in the `name` section of `module`, indexed
+/// by function index.
+fn function_names(module: &Module) -> HashMap<u32, String> {
+    let mut ret = HashMap::new();
+    let module = match module.clone().parse_names() {
+        Ok(m) => m,
+        Err((_, m)) => m,
+    };
+    for section in module.sections() {
+        let names = match section {
+            Section::Name(NameSection::Function(names)) => names.names(),
+            _ => continue,
+        };
+        for (idx, name) in names.iter() {
+            ret.insert(idx, name.clone());
+        }
+    }
+    ret
+}
+
+/// Quotes `s` as a JS string literal.
+fn js_string(s: &str) -> String {
+    let mut ret = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            ' '..='~' => ret.push(c),
+            _ => {
+                let mut buf = [0; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    ret.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+/// Returns the offset of each instruction of each function body in `wasm`,
+/// relative to the start of the code section's contents like addresses in
+/// DWARF for wasm are.
+///
+/// `parity-wasm` doesn't keep track of where instructions came from, so this
+/// decodes just enough of the code section to find out.
+fn instruction_offsets(wasm: &[u8]) -> Result<Vec<Vec<u32>>, Error> {
+    let mut module = Reader::new(wasm);
+    module.skip(8)?;
+    while !module.is_empty() {
+        let id = module.u8()?;
+        let size = module.uleb()? as usize;
+        let payload = module.bytes(size)?;
+        if id != 10 {
+            continue
+        }
+
+        let mut code = Reader::new(payload);
+        let mut ret = Vec::new();
+        for _ in 0..code.uleb()? {
+            let size = code.uleb()? as usize;
+            let end = code.pos + size;
+            let mut offsets = Vec::new();
+            for _ in 0..code.uleb()? {
+                code.uleb()?;
+                code.u8()?;
+            }
+            while code.pos < end {
+                offsets.push(code.pos as u32);
+                skip_instruction(&mut code)?;
+            }
+            ret.push(offsets);
+        }
+        return Ok(ret)
+    }
+    Ok(Vec::new())
+}
+
+/// Skips over one instruction, including its immediates.
+fn skip_instruction(code: &mut Reader) -> Result<(), Error> {
+    match code.u8()? {
+        // block, loop, if, memory.size, memory.grow
+        0x02..=0x04 | 0x3f | 0x40 => { code.u8()?; }
+        // br, br_if, call, local and global accesses, i32.const, i64.const
+        0x0c | 0x0d | 0x10 | 0x20..=0x24 | 0x41 | 0x42 => { code.uleb()?; }
+        // br_table
+        0x0e => {
+            for _ in 0..code.uleb()? + 1 {
+                code.uleb()?;
+            }
+        }
+        // call_indirect
+        0x11 => { code.uleb()?; code.u8()?; }
+        // loads and stores
+        0x28..=0x3e => { code.uleb()?; code.uleb()?; }
+        // f32.const, f64.const
+        0x43 => code.skip(4)?,
+        0x44 => code.skip(8)?,
+        // saturating truncations
+        0xfc => { code.uleb()?; }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The rows of DWARF line tables, mapping code addresses to source lines.
+struct LineTable {
+    /// Paths of all files referenced by the rows.
+    files: Vec<String>,
+    /// The address each row starts at, with the file and line of the
+    /// instructions from there on, or `None` for the end of a sequence.
+    rows: Vec<(u32, Option<(usize, u32)>)>,
+}
+
+impl LineTable {
+    /// Parses all line number programs in the `.debug_line` section
+    /// `section`. DWARF versions 2 through 4 are supported.
+    fn parse(section: &[u8]) -> Result<LineTable, Error> {
+        let mut table = LineTable {
+            files: Vec::new(),
+            rows: Vec::new(),
+        };
+        let mut interned = HashMap::new();
+        let mut section = Reader::new(section);
+        while !section.is_empty() {
+            let length = section.u32()? as usize;
+            if length == 0xffff_ffff {
+                bail!("64-bit DWARF isn't supported for coverage");
+            }
+            let mut unit = Reader::new(section.bytes(length)?);
+            table.parse_program(&mut unit, &mut interned)?;
+        }
+        table.rows.sort_by_key(|r| r.0);
+        Ok(table)
+    }
+
+    fn parse_program(
+        &mut self,
+        unit: &mut Reader,
+        interned: &mut HashMap<String, usize>,
+    ) -> Result<(), Error> {
+        let version = unit.u16()?;
+        if version < 2 || version > 4 {
+            bail!("DWARF version {} isn't supported for coverage", version);
+        }
+        let header_length = unit.u32()? as usize;
+        let program_start = unit.pos + header_length;
+        let min_instruction_length = u32::from(unit.u8()?);
+        if version >= 4 {
+            unit.u8()?; // maximum_operations_per_instruction
+        }
+        unit.u8()?; // default_is_stmt
+        let line_base = unit.u8()? as i8;
+        let line_range = unit.u8()?;
+        let opcode_base = unit.u8()?;
+        let standard_lengths = unit.bytes(usize::from(opcode_base.saturating_sub(1)))?.to_vec();
+        if line_range == 0 {
+            bail!("invalid line range in `.debug_line`");
+        }
+
+        let mut directories = vec![String::new()];
+        loop {
+            let dir = unit.cstr()?;
+            if dir.is_empty() {
+                break
+            }
+            directories.push(dir);
+        }
+        let mut files = vec![String::new()];
+        loop {
+            let name = unit.cstr()?;
+            if name.is_empty() {
+                break
+            }
+            files.push(file_path(&directories, &name, unit.uleb()?));
+            unit.uleb()?; // modification time
+            unit.uleb()?; // length
+        }
+        unit.pos = program_start;
+
+        let mut address = 0u32;
+        let mut file = 1u64;
+        let mut line = 1i64;
+        while !unit.is_empty() {
+            let mut emit = false;
+            let opcode = unit.u8()?;
+            if opcode >= opcode_base {
+                let adjusted = opcode - opcode_base;
+                address = address.wrapping_add(u32::from(adjusted / line_range) * min_instruction_length);
+                line += i64::from(line_base) + i64::from(adjusted % line_range);
+                emit = true;
+            } else {
+                match opcode {
+                    0 => {
+                        let length = unit.uleb()? as usize;
+                        let mut extended = Reader::new(unit.bytes(length)?);
+                        match extended.u8()? {
+                            // DW_LNE_end_sequence
+                            1 => {
+                                self.rows.push((address, None));
+                                address = 0;
+                                file = 1;
+                                line = 1;
+                            }
+                            // DW_LNE_set_address
+                            2 => address = extended.u32()?,
+                            // DW_LNE_define_file
+                            3 => {
+                                let name = extended.cstr()?;
+                                let dir = extended.uleb()?;
+                                files.push(file_path(&directories, &name, dir));
+                            }
+                            _ => {}
+                        }
+                    }
+                    // DW_LNS_copy
+                    1 => emit = true,
+                    // DW_LNS_advance_pc
+                    2 => {
+                        let delta = unit.uleb()? as u32;
+                        address = address.wrapping_add(delta * min_instruction_length);
+                    }
+                    // DW_LNS_advance_line
+                    3 => line += unit.sleb()?,
+                    // DW_LNS_set_file
+                    4 => file = unit.uleb()?,
+                    // DW_LNS_const_add_pc
+                    8 => {
+                        let adjusted = 255 - opcode_base;
+                        address = address.wrapping_add(u32::from(adjusted / line_range) * min_instruction_length);
+                    }
+                    // DW_LNS_fixed_advance_pc
+                    9 => address = address.wrapping_add(u32::from(unit.u16()?)),
+                    n => {
+                        for _ in 0..standard_lengths[usize::from(n) - 1] {
+                            unit.uleb()?;
+                        }
+                    }
+                }
+            }
+
+            if !emit {
+                continue
+            }
+            let path = match files.get(file as usize) {
+                Some(path) if line > 0 => path,
+                _ => continue,
+            };
+            let next = interned.len();
+            let index = *interned.entry(path.clone()).or_insert_with(|| {
+                self.files.push(path.clone());
+                next
+            });
+            self.rows.push((address, Some((index, line as u32))));
+        }
+        Ok(())
+    }
+
+    /// Returns the file and line of the instruction at `address`.
+    fn find(&self, address: u32) -> Option<(usize, u32)> {
+        let i = match self.rows.binary_search_by_key(&address, |r| r.0) {
+            // Several rows may start at the same address, the last one wins.
+            Ok(mut i) => {
+                while self.rows.get(i + 1).map(|r| r.0) == Some(address) {
+                    i += 1;
+                }
+                i
+            }
+            Err(0) => return None,
+            Err(i) => i - 1,
+        };
+        self.rows[i].1
+    }
+}
+
+/// Joins the file `name` onto its include directory `dir`.
+fn file_path(directories: &[String], name: &str, dir: u64) -> String {
+    match directories.get(dir as usize) {
+        Some(dir) if !dir.is_empty() && !name.starts_with('/') => {
+            format!("{}/{}", dir, name)
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// A cursor over little-endian binary data.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader { data, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.data.len().saturating_sub(self.pos) < n {
+            bail!("unexpected end of data");
+        }
+        let ret = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(ret)
+    }
+
+    fn skip(&mut self, n: usize) -> Result<(), Error> {
+        self.bytes(n).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let b = self.bytes(2)?;
+        Ok(u16::from(b[0]) | u16::from(b[1]) << 8)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let b = self.bytes(4)?;
+        Ok(b.iter().rev().fold(0, |n, b| n << 8 | u32::from(*b)))
+    }
+
+    fn uleb(&mut self) -> Result<u64, Error> {
+        let mut ret = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                ret |= u64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(ret)
+            }
+        }
+    }
+
+    fn sleb(&mut self) -> Result<i64, Error> {
+        let mut ret = 0i64;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                ret |= i64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    ret |= -1 << shift;
+                }
+                return Ok(ret)
+            }
+        }
+    }
+
+    fn cstr(&mut self) -> Result<String, Error> {
+        let rest = self.data.get(self.pos..).unwrap_or(&[]);
+        let len = match rest.iter().position(|b| *b == 0) {
+            Some(len) => len,
+            None => bail!("unterminated string"),
+        };
+        let s = String::from_utf8_lossy(self.bytes(len)?).into_owned();
+        self.pos += 1;
+        Ok(s)
+    }
+}
@@ -13,6 +13,14 @@ use serde_json;
 
 use shell::Shell;
 
+/// A browser requested with `--browser`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Browser {
+    Firefox,
+    Chrome,
+    Safari,
+}
+
 /// Execute a headless browser tests against a server running on `server`
 /// address.
 ///
@@ -20,8 +28,16 @@ use shell::Shell;
 /// binary, controlling it, running tests, scraping output, displaying output,
 /// etc. It will return `Ok` if all tests finish successfully, and otherwise it
 /// will return an error if some tests failed.
-pub fn run(server: &SocketAddr, shell: &Shell) -> Result<(), Error> {
-    let (driver, args) = Driver::find()?;
+///
+/// Once tests have finished this also waits for the page to post back all
+/// machine-readable `reports` requested, which are written by the server.
+pub fn run(
+    server: &SocketAddr,
+    shell: &Shell,
+    browser: Option<Browser>,
+    reports: &[PathBuf],
+) -> Result<(), Error> {
+    let (driver, args) = Driver::find(browser)?;
     println!("Running headless tests in {} with `{}`",
              driver.browser(),
              driver.path().display());
@@ -75,6 +91,7 @@ pub fn run(server: &SocketAddr, shell: &Shell) -> Result<(), Error> {
     let output = client.element(&id, "#output")?;
     let logs = client.element(&id, "#console_log")?;
     let errors = client.element(&id, "#console_error")?;
+    let uncaught = client.element(&id, "#uncaught")?;
 
     // At this point we need to wait for the test to finish before we can take a
     // look at what happened. There appears to be no great way to do this with
@@ -82,22 +99,48 @@ pub fn run(server: &SocketAddr, shell: &Shell) -> Result<(), Error> {
     // just go with a loop.
     //
     // We periodically check the page to see if the output contains a known
-    // string to only be printed when tests have finished running.
+    // string to only be printed when tests have finished running. As long as
+    // tests keep making progress we keep waiting, but if the output doesn't
+    // change for a while (20s by default, configurable through
+    // `WASM_BINDGEN_TEST_TIMEOUT` in seconds) we give up. Hung asynchronous
+    // tests are timed out by the harness itself well before that.
     //
-    // TODO: harness failures aren't well handled here, they always force a
-    //       timeout. These sorts of failures could be "you typo'd the path to a
-    //       local script" which is pretty bad to time out for, we should detect
-    //       this on the page and look for such output here, printing diagnostic
-    //       information.
+    // Harness failures, like "you typo'd the path to a local script", mean
+    // the tests never finish. These are detected on the page so we can stop
+    // waiting early, and along with all other uncaught errors are printed.
     shell.status("Waiting for test to finish...");
-    let start = Instant::now();
-    let max = Duration::new(20, 0);
+    let max = env::var("WASM_BINDGEN_TEST_TIMEOUT")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(|secs| Duration::new(secs, 0))
+        .unwrap_or(Duration::new(20, 0));
+    let mut start = Instant::now();
+    let mut last_output = String::new();
     while start.elapsed() < max {
-        if client.text(&id, &output)?.contains("test result: ") {
+        let text = client.text(&id, &output)?;
+        if text.contains("test result: ") {
             break
         }
+        if client.text(&id, &uncaught)?.contains("harness failed: ") {
+            break
+        }
+        if text != last_output {
+            start = Instant::now();
+            last_output = text;
+        }
         thread::sleep(Duration::from_millis(100));
     }
+
+    // Reports are posted to the server after the test result is printed, so
+    // give the page a moment to finish sending them.
+    if reports.len() > 0 {
+        shell.status("Waiting for test reports...");
+        let start = Instant::now();
+        let max = Duration::new(5, 0);
+        while start.elapsed() < max && !reports.iter().all(|p| p.exists()) {
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
     shell.clear();
 
     // Tests have now finished or have timed out. At this point we need to print
@@ -107,6 +150,7 @@ pub fn run(server: &SocketAddr, shell: &Shell) -> Result<(), Error> {
     let output = client.text(&id, &output)?;
     let logs = client.text(&id, &logs)?;
     let errors = client.text(&id, &errors)?;
+    let uncaught = client.text(&id, &uncaught)?;
 
     if output.contains("test result: ") {
         println!("{}", output);
@@ -125,7 +169,16 @@ pub fn run(server: &SocketAddr, shell: &Shell) -> Result<(), Error> {
         println!("console.log div contained:\n{}", tab(&logs));
     }
     if errors.len() > 0 {
-        println!("console.log div contained:\n{}", tab(&errors));
+        println!("console.error div contained:\n{}", tab(&errors));
+    }
+    if uncaught.len() > 0 {
+        println!("uncaught errors on the page:\n{}", tab(&uncaught));
+    }
+
+    for report in reports {
+        if !report.exists() {
+            println!("failed to receive test report {}", report.display());
+        }
     }
 
     if !output.contains("test result: ok") {
@@ -151,7 +204,9 @@ impl Driver {
     /// In both cases a list of auxiliary arguments is also returned which is
     /// configured through env vars like `GECKODRIVER_ARGS` to support extra
     /// arguments to the driver's invocation.
-    fn find() -> Result<(Driver, Vec<String>), Error> {
+    ///
+    /// If a `browser` is specified only its driver is searched for.
+    fn find(browser: Option<Browser>) -> Result<(Driver, Vec<String>), Error> {
         let env_args = |name: &str| {
             env::var(format!("{}_ARGS", name.to_uppercase()))
                 .unwrap_or_default()
@@ -161,10 +216,15 @@ impl Driver {
         };
 
         let drivers = [
-            ("geckodriver", Driver::Gecko as fn(PathBuf) -> Driver),
-            ("safaridriver", Driver::Safari as fn(PathBuf) -> Driver),
-            ("chromedriver", Driver::Chrome as fn(PathBuf) -> Driver),
+            ("geckodriver", Browser::Firefox, Driver::Gecko as fn(PathBuf) -> Driver),
+            ("safaridriver", Browser::Safari, Driver::Safari as fn(PathBuf) -> Driver),
+            ("chromedriver", Browser::Chrome, Driver::Chrome as fn(PathBuf) -> Driver),
         ];
+        let drivers = drivers
+            .iter()
+            .filter(|(_, b, _)| browser.map(|browser| browser == *b).unwrap_or(true))
+            .map(|(name, _, ctor)| (*name, *ctor))
+            .collect::<Vec<_>>();
 
         // First up, if env vars like GECKODRIVER are present, use those to
         // allow forcing usage of a particular driver.
@@ -197,6 +257,17 @@ impl Driver {
         // TODO: download an appropriate driver? How to know which one to
         //       download?
 
+        if let Some((name, _)) = drivers.first().filter(|_| browser.is_some()) {
+            bail!("\
+failed to find `{0}` to drive headless testing in the requested browser; make
+sure that the binary is in `PATH` or configure its location with an environment
+variable like `{1}=/path/to/{0}`
+",
+                name,
+                name.to_uppercase(),
+            )
+        }
+
         bail!("\
 failed to find a suitable WebDriver binary to drive headless testing; to
 configure the location of the webdriver binary you can use environment
@@ -12,6 +12,7 @@
 //! and source code.
 
 extern crate curl;
+extern crate docopt;
 extern crate env_logger;
 #[macro_use]
 extern crate failure;
@@ -19,31 +20,90 @@ extern crate failure;
 extern crate log;
 extern crate parity_wasm;
 extern crate rouille;
+extern crate rustc_demangle;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 #[macro_use]
 extern crate serde_json;
 extern crate wasm_bindgen_cli_support;
+extern crate wasm_bindgen_shared;
 
 use std::env;
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::thread;
 
+use docopt::Docopt;
 use failure::{ResultExt, Error};
 use parity_wasm::elements::{Module, Deserialize, Section};
 use wasm_bindgen_cli_support::Bindgen;
 
+mod coverage;
 mod headless;
 mod node;
 mod server;
 mod shell;
 
+const USAGE: &'static str = "
+Execute tests of a wasm file built with `wasm-bindgen-test`
+
+Usage:
+    wasm-bindgen-test-runner [options] <input> [<args>...]
+    wasm-bindgen-test-runner -h | --help
+    wasm-bindgen-test-runner -V | --version
+
+Arguments after the wasm file are forwarded to the test harness, for example
+test filters and flags like `--nocapture`.
+
+Options:
+    -h --help          Show this screen.
+    --tmp-dir DIR      Directory to generate files in, defaults to `wbg-tmp` in
+                       Cargo's target directory for the wasm file
+    --keep-tmp         Don't clear out the temporary directory beforehand
+    --address ADDR     Address to serve browser tests on, defaults to
+                       127.0.0.1:8000 for interactive tests and a random port
+                       for headless tests
+    --node             Run tests in node.js, regardless of configuration
+    --browser BROWSER  Run tests in a headless `firefox`, `chrome`, or `safari`,
+                       regardless of configuration
+    --no-debug         Don't include debug checks in the generated JS
+    --coverage         Count which lines and functions run and write an lcov
+                       report to `coverage.lcov` in the temporary directory
+    -V --version       Print the version number of wasm-bindgen-test-runner
+";
+
+#[derive(Debug, Deserialize)]
+struct Args {
+    flag_tmp_dir: Option<PathBuf>,
+    flag_keep_tmp: bool,
+    flag_address: Option<String>,
+    flag_node: bool,
+    flag_browser: Option<String>,
+    flag_no_debug: bool,
+    flag_coverage: bool,
+    flag_version: bool,
+    arg_input: Option<PathBuf>,
+    arg_args: Vec<String>,
+}
+
 fn main() {
     env_logger::init();
-    let err = match rmain() {
+
+    // Stop parsing our own options at the wasm file, as everything after it
+    // is forwarded to the test harness.
+    let args: Args = Docopt::new(USAGE)
+        .and_then(|d| d.options_first(true).deserialize())
+        .unwrap_or_else(|e| e.exit());
+
+    if args.flag_version {
+        println!("wasm-bindgen-test-runner {}", wasm_bindgen_shared::version());
+        return;
+    }
+    let err = match rmain(&args) {
         Ok(()) => return,
         Err(e) => e,
     };
@@ -54,30 +114,59 @@ fn main() {
     process::exit(1);
 }
 
-fn rmain() -> Result<(), Error> {
-    let mut args = env::args_os().skip(1);
+fn rmain(args: &Args) -> Result<(), Error> {
     let shell = shell::Shell::new();
 
-    // Currently no flags are supported, and assume there's only one argument
-    // which is the wasm file to test. This'll want to improve over time!
-    let wasm_file_to_test = match args.next() {
-        Some(file) => PathBuf::from(file),
+    let wasm_file_to_test = match &args.arg_input {
+        Some(file) => file,
         None => bail!("must have a file to test as first argument"),
     };
+    let harness_args = args.arg_args.iter()
+        .map(OsString::from)
+        .collect::<Vec<_>>();
+
+    if args.flag_node && args.flag_browser.is_some() {
+        bail!("cannot specify both `--node` and `--browser`");
+    }
+    let browser = match args.flag_browser.as_ref().map(|s| &s[..]) {
+        None => None,
+        Some("firefox") => Some(headless::Browser::Firefox),
+        Some("chrome") => Some(headless::Browser::Chrome),
+        Some("safari") => Some(headless::Browser::Safari),
+        Some(other) => {
+            bail!("unknown browser `{}`, expected `firefox`, `chrome`, or `safari`", other)
+        }
+    };
+    let address = match &args.flag_address {
+        Some(addr) => {
+            Some(addr.parse::<SocketAddr>()
+                .with_context(|_| format!("invalid address `{}`", addr))?)
+        }
+        None => None,
+    };
 
-    // Assume a cargo-like directory layout and generate output at
-    // `target/wasm32-unknown-unknown/wbg-tmp/...`
-    let tmpdir = wasm_file_to_test.parent() // chop off file name
-        .and_then(|p| p.parent())           // chop off `deps`
-        .and_then(|p| p.parent())           // chop off `debug`
-        .map(|p| p.join("wbg-tmp"))
-        .ok_or_else(|| {
-            format_err!("file to test doesn't follow the expected Cargo conventions")
-        })?;
+    // Unless told otherwise assume a cargo-like directory layout and generate
+    // output at `target/wasm32-unknown-unknown/wbg-tmp/...`
+    let tmpdir = match &args.flag_tmp_dir {
+        Some(dir) => dir.clone(),
+        None => {
+            wasm_file_to_test.parent()      // chop off file name
+                .and_then(|p| p.parent())   // chop off `deps`
+                .and_then(|p| p.parent())   // chop off `debug`
+                .map(|p| p.join("wbg-tmp"))
+                .ok_or_else(|| {
+                    format_err!("file to test doesn't follow the expected Cargo \
+                                 conventions, use `--tmp-dir` to configure where \
+                                 to generate files")
+                })?
+        }
+    };
 
     // Make sure there's no stale state from before
-    drop(fs::remove_dir_all(&tmpdir));
-    fs::create_dir(&tmpdir)
+    if !args.flag_keep_tmp {
+        drop(fs::remove_dir_all(&tmpdir));
+    }
+    fs::create_dir_all(&tmpdir)
         .context("creating temporary directory")?;
 
     let module = "wasm-bindgen-test";
@@ -85,9 +174,9 @@ fn rmain() -> Result<(), Error> {
     // Collect all tests that the test harness is supposed to run. We assume
     // that any exported function with the prefix `__wbg_test` is a test we need
     // to execute.
-    let wasm = fs::read(&wasm_file_to_test)
+    let bytes = fs::read(wasm_file_to_test)
         .context("failed to read wasm file")?;
-    let wasm = Module::deserialize(&mut &wasm[..])
+    let mut wasm = Module::deserialize(&mut &bytes[..])
         .context("failed to deserialize wasm module")?;
     let mut tests = Vec::new();
     if let Some(exports) = wasm.export_section() {
@@ -107,11 +196,13 @@ fn rmain() -> Result<(), Error> {
         return Ok(())
     }
 
-    // Figure out if this tests is supposed to execute in node.js or a browser.
-    // That's done on a per-test-binary basis with the
-    // `wasm_bindgen_test_configure` macro, which emits a custom section for us
-    // to read later on.
+    // Figure out if this tests is supposed to execute in node.js, a browser,
+    // or a web worker in a browser. That's done on a per-test-binary basis
+    // with the `wasm_bindgen_test_configure` macro, which emits a custom
+    // section for us to read later on, and can be overridden with `--node`
+    // and `--browser`.
     let mut node = true;
+    let mut worker = None;
     for section in wasm.sections() {
         let custom = match section {
             Section::Custom(section) => section,
@@ -120,16 +211,56 @@ fn rmain() -> Result<(), Error> {
         if custom.name() != "__wasm_bindgen_test_unstable" {
             continue
         }
-        node = !custom.payload().contains(&0x01);
+        let payload = custom.payload();
+        if payload.contains(&0x03) {
+            worker = Some(server::Worker::Shared);
+        } else if payload.contains(&0x02) {
+            worker = Some(server::Worker::Dedicated);
+        }
+        node = worker.is_none() && !payload.contains(&0x01);
+    }
+    if args.flag_node {
+        node = true;
+        worker = None;
+    } else if browser.is_some() {
+        node = false;
     }
     let headless = env::var("NO_HEADLESS").is_err();
-    let debug = env::var("WASM_BINDGEN_NO_DEBUG").is_err();
+    let debug = !args.flag_no_debug && env::var("WASM_BINDGEN_NO_DEBUG").is_err();
+
+    // Machine-readable reports of the test run are requested as a
+    // comma-separated list of formats, and are written into our temporary
+    // directory once all tests have finished.
+    let reports = env::var("WASM_BINDGEN_TEST_REPORT")
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| s.len() > 0)
+        .collect::<Vec<_>>();
+    for format in reports.iter() {
+        if format != "json" && format != "junit" {
+            bail!("unknown report format `{}` in `WASM_BINDGEN_TEST_REPORT`, \
+                   expected `json` or `junit`", format);
+        }
+    }
+
+    // Instrument the module to count which code runs before `wasm-bindgen`
+    // sees it, if requested.
+    let coverage = if args.flag_coverage || env::var("WASM_BINDGEN_TEST_COVERAGE").is_ok() {
+        Some(coverage::instrument(&mut wasm, &bytes)
+            .context("failed to instrument wasm file for coverage")?)
+    } else {
+        None
+    };
 
     // Make the generated bindings available for the tests to execute against.
+    // Workers can't load ES modules, so they get bindings which don't use
+    // modules.
     shell.status("Executing bindgen...");
     let mut b = Bindgen::new();
     b.debug(debug)
         .nodejs(node)
+        .no_modules(worker.is_some())
         .input_module(module, wasm, |w| parity_wasm::serialize(w).unwrap())
         .keep_debug(false)
         .generate(&tmpdir)
@@ -138,22 +269,35 @@ fn rmain() -> Result<(), Error> {
 
     // If we're executing in node.js, that module will take it from here.
     if node {
-        return node::execute(&module, &tmpdir, &args.collect::<Vec<_>>(), &tests)
+        return node::execute(
+            &module,
+            &tmpdir,
+            &harness_args,
+            &tests,
+            &reports,
+            coverage.as_ref(),
+        )
     }
 
     // Otherwise we're executing in a browser. Spawn a server which serves up
     // the local generated files over an HTTP server.
-    let srv = server::spawn(
-        &if headless {
+    let addr = address.unwrap_or_else(|| {
+        if headless {
             "127.0.0.1:0".parse().unwrap()
         } else {
             "127.0.0.1:8000".parse().unwrap()
-        },
+        }
+    });
+    let srv = server::spawn(
+        &addr,
         headless,
+        worker,
         &module,
         &tmpdir,
-        &args.collect::<Vec<_>>(),
+        &harness_args,
         &tests,
+        &reports,
+        coverage.as_ref(),
     )?;
     let addr = srv.server_addr();
 
@@ -170,6 +314,21 @@ fn rmain() -> Result<(), Error> {
     }
 
     thread::spawn(|| srv.run());
-    headless::run(&addr, &shell)?;
+    let mut reports = reports.iter()
+        .map(|format| report_path(&tmpdir, format))
+        .collect::<Vec<_>>();
+    if coverage.is_some() {
+        reports.push(report_path(&tmpdir, "lcov"));
+    }
+    headless::run(&addr, &shell, browser, &reports)?;
     Ok(())
 }
+
+/// Returns the path in `tmpdir` that a report in `format` is written to.
+fn report_path(tmpdir: &Path, format: &str) -> PathBuf {
+    match format {
+        "junit" => tmpdir.join("report.xml"),
+        "lcov" => tmpdir.join("coverage.lcov"),
+        _ => tmpdir.join(format!("report.{}", format)),
+    }
+}
@@ -6,9 +6,16 @@ use std::process::Command;
 
 use failure::{ResultExt, Error};
 
-pub fn execute(module: &str, tmpdir: &Path, args: &[OsString], tests: &[String])
-    -> Result<(), Error>
-{
+use coverage::Coverage;
+
+pub fn execute(
+    module: &str,
+    tmpdir: &Path,
+    args: &[OsString],
+    tests: &[String],
+    reports: &[String],
+    coverage: Option<&Coverage>,
+) -> Result<(), Error> {
     let mut js_to_execute = format!(r#"
         const {{ exit }} = require('process');
 
@@ -37,6 +44,11 @@ pub fn execute(module: &str, tmpdir: &Path, args: &[OsString], tests: &[String])
 
         global.__wbg_test_invoke = f => f();
 
+        // Benchmarks are timed with `performance.now()`, which older versions
+        // of node only provide through `perf_hooks`.
+        if (typeof performance === 'undefined')
+            global.performance = require('perf_hooks').performance;
+
         async function main(tests) {{
             const support = require("./{0}");
             const wasm = require("./{0}_bg");
@@ -47,15 +59,22 @@ pub fn execute(module: &str, tmpdir: &Path, args: &[OsString], tests: &[String])
 
             // Forward runtime arguments. These arguments are also arguments to the
             // `wasm-bindgen-test-runner` which forwards them to node which we
-            // forward to the test harness. this is basically only used for test
-            // filters for now.
+            // forward to the test harness, which handles test filters and
+            // libtest-like flags such as `--exact` and `--nocapture`.
             cx.args(process.argv.slice(2));
 
             const ok = await cx.run(tests.map(n => wasm[n]));
+            for (const [format, path] of reports)
+                require('fs').writeFileSync(path, cx.report(format));
+            if (coverage !== null)
+                require('fs').writeFileSync(coverage, __wbg_test_coverage(wasm.memory));
             if (!ok)
                 exit(1);
         }}
 
+        const reports = [];
+        let coverage = null;
+
         const tests = [];
     "#,
         module
@@ -67,6 +86,19 @@ pub fn execute(module: &str, tmpdir: &Path, args: &[OsString], tests: &[String])
     for test in tests {
         js_to_execute.push_str(&format!("tests.push('{}')\n", test));
     }
+    if let Some(coverage) = coverage {
+        js_to_execute.push_str(&coverage.js());
+        let path = ::report_path(tmpdir, "lcov");
+        js_to_execute.push_str(&format!("coverage = {:?};\n", path.display().to_string()));
+    }
+    for format in reports {
+        let path = ::report_path(tmpdir, format);
+        js_to_execute.push_str(&format!(
+            "reports.push(['{}', {:?}])\n",
+            format,
+            path.display().to_string(),
+        ));
+    }
     // And as a final addendum, exit with a nonzero code if any tests fail.
     js_to_execute.push_str("
         main(tests)
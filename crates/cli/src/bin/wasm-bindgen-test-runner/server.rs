@@ -1,79 +1,48 @@
 use std::ffi::OsString;
 use std::path::Path;
 use std::fs;
+use std::io::Read;
 use std::net::SocketAddr;
 
 use failure::{ResultExt, Error};
 use rouille::{self, Response, Request, Server};
 use wasm_bindgen_cli_support::wasm2es6js::Config;
 
+use coverage::Coverage;
+
+/// The kind of web worker tests are executed in, configured with
+/// `wasm_bindgen_test_configure!`.
+#[derive(Clone, Copy)]
+pub enum Worker {
+    Dedicated,
+    Shared,
+}
+
 pub fn spawn(
     addr: &SocketAddr,
     headless: bool,
+    worker: Option<Worker>,
     module: &str,
     tmpdir: &Path,
     args: &[OsString],
     tests: &[String],
+    reports: &[String],
+    coverage: Option<&Coverage>,
 ) -> Result<Server<impl Fn(&Request) -> Response + Send + Sync>, Error> {
-    let mut js_to_execute = format!(r#"
-        import {{ Context, __wbgtest_console_log, __wbgtest_console_error }} from './{0}';
-        import * as wasm from './{0}_bg';
-
-        // Now that we've gotten to the point where JS is executing, update our
-        // status text as at this point we should be asynchronously fetching the
-        // wasm module.
-        document.getElementById('output').innerHTML = "Loading wasm module...";
-
-        async function main(test) {{
-            // this is a facet of using wasm2es6js, a hack until browsers have
-            // native ESM support for wasm modules.
-            await wasm.booted;
-
-            const cx = Context.new();
-            window.console_log_redirect = __wbgtest_console_log;
-            window.console_error_redirect = __wbgtest_console_error;
-
-            // Forward runtime arguments. These arguments are also arguments to the
-            // `wasm-bindgen-test-runner` which forwards them to node which we
-            // forward to the test harness. this is basically only used for test
-            // filters for now.
-            cx.args({1:?});
-
-            await cx.run(test.map(s => wasm[s]));
-        }}
-
-        const tests = [];
-    "#,
-        module, args,
-    );
-    for test in tests {
-        js_to_execute.push_str(&format!("tests.push('{}');\n", test));
+    let coverage_js = coverage.map(|c| c.js()).unwrap_or_default();
+    match worker {
+        None => write_module_js(module, tmpdir, args, tests, reports, &coverage_js)?,
+        Some(worker) => {
+            write_worker_js(worker, module, tmpdir, args, tests, reports, &coverage_js)?
+        }
     }
-    js_to_execute.push_str("main(tests);\n");
-
-    let js_path = tmpdir.join("run.js");
-    fs::write(&js_path, js_to_execute)
-        .context("failed to write JS file")?;
-
-    // No browser today supports a wasm file as ES modules natively, so we need
-    // to shim it. Use `wasm2es6js` here to fetch an appropriate URL and look
-    // like an ES module with the wasm module under the hood.
-    //
-    // TODO: don't reparse the wasm module here, should pass the
-    //       `parity_wasm::Module struct` directly from the output of
-    //       `wasm-bindgen` previously here and avoid unnecessary
-    //       parsing.
-    let wasm_name = format!("{}_bg.wasm", module);
-    let wasm = fs::read(tmpdir.join(&wasm_name))?;
-    let output = Config::new()
-        .fetch(Some(format!("/{}", wasm_name)))
-        .generate(&wasm)?;
-    let js = output.js()?;
-    fs::write(tmpdir.join(format!("{}_bg.js", module)), js)
-        .context("failed to write JS file")?;
 
     // For now, always run forever on this port. We may update this later!
     let tmpdir = tmpdir.to_path_buf();
+    let mut reports = reports.to_vec();
+    if coverage.is_some() {
+        reports.push("lcov".to_string());
+    }
     let srv = Server::new(addr, move |request| {
         // The root path gets our canned `index.html`. The two templates here
         // differ slightly in the default routing of `console.log`, going to an
@@ -88,6 +57,28 @@ pub fn spawn(
             return Response::from_data("text/html", s)
         }
 
+        // Reports of the test run are posted back to us to get written out
+        if request.method() == "POST" {
+            let format = request.url().trim_left_matches("/__wbgtest_report/").to_string();
+            if !reports.contains(&format) {
+                return Response::empty_404()
+            }
+            let mut report = Vec::new();
+            if let Some(mut data) = request.data() {
+                if data.read_to_end(&mut report).is_err() {
+                    return Response::text("failed to read report").with_status_code(400)
+                }
+            }
+            // Write to a temporary file first so a complete report appears at
+            // once for the headless runner waiting on it.
+            let path = ::report_path(&tmpdir, &format);
+            let tmp = path.with_extension("tmp");
+            if fs::write(&tmp, &report).and_then(|()| fs::rename(&tmp, &path)).is_err() {
+                return Response::text("failed to write report").with_status_code(500)
+            }
+            return Response::empty_204()
+        }
+
         // Otherwise we need to find the asset here. It may either be in our
         // temporary directory (generated files) or in the main directory
         // (relative import paths to JS). Try to find both locations.
@@ -131,3 +122,264 @@ pub fn spawn(
         response
     }
 }
+
+/// Writes `run.js` which executes tests directly on the page, importing the
+/// generated bindings as ES modules.
+fn write_module_js(
+    module: &str,
+    tmpdir: &Path,
+    args: &[OsString],
+    tests: &[String],
+    reports: &[String],
+    coverage_js: &str,
+) -> Result<(), Error> {
+    let mut js_to_execute = format!(r#"
+        import {{ Context, __wbgtest_console_log, __wbgtest_console_error }} from './{0}';
+        import * as wasm from './{0}_bg';
+
+        // Now that we've gotten to the point where JS is executing, update our
+        // status text as at this point we should be asynchronously fetching the
+        // wasm module.
+        document.getElementById('output').innerHTML = "Loading wasm module...";
+
+        async function main(test) {{
+            // this is a facet of using wasm2es6js, a hack until browsers have
+            // native ESM support for wasm modules.
+            await wasm.booted;
+
+            const cx = Context.new();
+            window.console_log_redirect = __wbgtest_console_log;
+            window.console_error_redirect = __wbgtest_console_error;
+
+            // Forward runtime arguments. These arguments are also arguments to the
+            // `wasm-bindgen-test-runner` which forwards them to node which we
+            // forward to the test harness. this is basically only used for test
+            // filters for now.
+            cx.args({1:?});
+
+            await cx.run(test.map(s => wasm[s]));
+
+            // Machine-readable reports can't be written from the browser, so
+            // send them back to the server which writes them to disk.
+            for (const format of {2:?}) {{
+                await fetch(`/__wbgtest_report/${{format}}`, {{
+                    method: 'POST',
+                    body: cx.report(format),
+                }});
+            }}
+            if (typeof __wbg_test_coverage === 'function') {{
+                await fetch('/__wbgtest_report/lcov', {{
+                    method: 'POST',
+                    body: __wbg_test_coverage(wasm.memory),
+                }});
+            }}
+        }}
+
+        const tests = [];
+    "#,
+        module, args, reports,
+    );
+    js_to_execute.push_str(coverage_js);
+    for test in tests {
+        js_to_execute.push_str(&format!("tests.push('{}');\n", test));
+    }
+    js_to_execute.push_str("
+        main(tests).catch(e => {
+            const msg = `harness failed: ${e && e.stack ? e.stack : e}`;
+            if (window.__wbg_test_uncaught)
+                window.__wbg_test_uncaught(msg);
+            else
+                console.error(msg);
+        });
+    ");
+
+    let js_path = tmpdir.join("run.js");
+    fs::write(&js_path, js_to_execute)
+        .context("failed to write JS file")?;
+
+    // No browser today supports a wasm file as ES modules natively, so we need
+    // to shim it. Use `wasm2es6js` here to fetch an appropriate URL and look
+    // like an ES module with the wasm module under the hood.
+    //
+    // TODO: don't reparse the wasm module here, should pass the
+    //       `parity_wasm::Module struct` directly from the output of
+    //       `wasm-bindgen` previously here and avoid unnecessary
+    //       parsing.
+    let wasm_name = format!("{}_bg.wasm", module);
+    let wasm = fs::read(tmpdir.join(&wasm_name))?;
+    let output = Config::new()
+        .fetch(Some(format!("/{}", wasm_name)))
+        .generate(&wasm)?;
+    let js = output.js()?;
+    fs::write(tmpdir.join(format!("{}_bg.js", module)), js)
+        .context("failed to write JS file")?;
+
+    Ok(())
+}
+
+/// Writes `worker.js`, which executes tests in a web worker, along with a
+/// `run.js` for the page which spawns the worker and relays the worker's test
+/// output, console output, and reports back onto the page.
+///
+/// Workers can't import ES modules, so bindings here are generated without
+/// modules and loaded with `importScripts`.
+fn write_worker_js(
+    worker: Worker,
+    module: &str,
+    tmpdir: &Path,
+    args: &[OsString],
+    tests: &[String],
+    reports: &[String],
+    coverage_js: &str,
+) -> Result<(), Error> {
+    let mut js_to_execute = format!(r#"
+        importScripts('/{0}.js');
+
+        // Messages to the page are queued up until we know how to send them,
+        // which for shared workers is only once a page connects.
+        const pending = [];
+        let post = msg => pending.push(msg);
+
+        // Route all output of the test harness and console to the page, see
+        // `index.html` for how console output is captured there.
+        const stringify = args => Array.prototype.map.call(args, String).join(' ');
+        const orig_console_log = function() {{
+            post(['console_log', stringify(arguments)]);
+        }};
+        const orig_console_error = function() {{
+            post(['console_error', stringify(arguments)]);
+        }};
+        console.log = function() {{
+            if (self.console_log_redirect)
+                self.console_log_redirect(orig_console_log, arguments);
+            else
+                orig_console_log.apply(this, arguments);
+        }};
+        console.error = function() {{
+            if (self.console_error_redirect)
+                self.console_error_redirect(orig_console_error, arguments);
+            else
+                orig_console_error.apply(this, arguments);
+        }};
+        self.__wbg_test_invoke = f => f();
+        self.__wbg_test_output_writeln = line => post(['output', line]);
+
+        // Uncaught errors in the worker are reported to the page through the
+        // worker's `error` event, but unhandled rejections need to be sent.
+        self.addEventListener('unhandledrejection', e => {{
+            const reason = e.reason && e.reason.stack ? e.reason.stack : `${{e.reason}}`;
+            post(['uncaught', `unhandled promise rejection: ${{reason}}`]);
+        }});
+
+        async function main(tests) {{
+            await wasm_bindgen('/{0}_bg.wasm');
+            const wasm = wasm_bindgen.wasm;
+
+            const cx = new wasm_bindgen.Context();
+            self.console_log_redirect = wasm_bindgen.__wbgtest_console_log;
+            self.console_error_redirect = wasm_bindgen.__wbgtest_console_error;
+
+            // Forward runtime arguments, see `run.js` for non-worker tests.
+            cx.args({1:?});
+
+            await cx.run(tests.map(s => wasm[s]));
+
+            // Reports are sent to the page which posts them to the server.
+            for (const format of {2:?})
+                post(['report', format, cx.report(format)]);
+            if (typeof __wbg_test_coverage === 'function')
+                post(['report', 'lcov', __wbg_test_coverage(wasm.memory)]);
+        }}
+
+        function start(send) {{
+            post = send;
+            for (const msg of pending.splice(0))
+                post(msg);
+            main(tests).catch(e => post(['uncaught', `harness failed: ${{e && e.stack ? e.stack : e}}`]));
+        }}
+
+        const tests = [];
+    "#,
+        module, args, reports,
+    );
+    js_to_execute.push_str(coverage_js);
+    for test in tests {
+        js_to_execute.push_str(&format!("tests.push('{}');\n", test));
+    }
+    js_to_execute.push_str(match worker {
+        Worker::Dedicated => "start(msg => self.postMessage(msg));\n",
+        Worker::Shared => "
+            let started = false;
+            self.onconnect = e => {
+                const port = e.ports[0];
+                if (started)
+                    return;
+                started = true;
+                start(msg => port.postMessage(msg));
+            };
+        ",
+    });
+    fs::write(tmpdir.join("worker.js"), js_to_execute)
+        .context("failed to write JS file")?;
+
+    let spawn_worker = match worker {
+        Worker::Dedicated => "
+            const worker = new Worker('worker.js');
+            const port = worker;
+        ",
+        Worker::Shared => "
+            const worker = new SharedWorker('worker.js');
+            const port = worker.port;
+            port.start();
+        ",
+    };
+    let page_js = format!(r#"
+        const output = document.getElementById('output');
+        output.innerHTML = "Loading wasm module in a worker...";
+        let cleared = false;
+
+        const uncaught = msg => {{
+            if (window.__wbg_test_uncaught)
+                window.__wbg_test_uncaught(msg);
+            else
+                console.error(msg);
+        }};
+
+        {}
+        worker.onerror = e => {{
+            uncaught(`uncaught error in worker: ${{e.message}} (${{e.filename}}:${{e.lineno}})`);
+        }};
+        port.onmessage = async e => {{
+            const [kind, text, report] = e.data;
+            switch (kind) {{
+                case 'output':
+                    if (!cleared) {{
+                        output.innerHTML = '';
+                        cleared = true;
+                    }}
+                    output.textContent += text + '\n';
+                    break;
+                case 'console_log':
+                    console.log(text);
+                    break;
+                case 'console_error':
+                    console.error(text);
+                    break;
+                case 'uncaught':
+                    uncaught(text);
+                    break;
+                case 'report':
+                    await fetch(`/__wbgtest_report/${{text}}`, {{
+                        method: 'POST',
+                        body: report,
+                    }});
+                    break;
+            }}
+        }};
+    "#,
+        spawn_worker,
+    );
+    fs::write(tmpdir.join("run.js"), page_js)
+        .context("failed to write JS file")?;
+    Ok(())
+}
@@ -24,6 +24,9 @@ Usage:
 Options:
     -h --help                Show this screen.
     --out-dir DIR            Output directory
+    --out-name NAME          Base name of the generated JS and TypeScript files
+    --wasm-name NAME         Name of the generated wasm file, without extension
+    --wasm-import-path PATH  Module specifier the JS uses to import the wasm
     --nodejs                 Generate output that only works in node.js
     --browser                Generate output that only works in a browser
     --no-modules             Generate output that only works in a browser (without modules)
@@ -44,6 +47,9 @@ struct Args {
     flag_typescript: bool,
     flag_no_typescript: bool,
     flag_out_dir: Option<PathBuf>,
+    flag_out_name: Option<String>,
+    flag_wasm_name: Option<String>,
+    flag_wasm_import_path: Option<String>,
     flag_debug: bool,
     flag_version: bool,
     flag_no_demangle: bool,
@@ -92,6 +98,15 @@ fn rmain(args: &Args) -> Result<(), Error> {
     if let Some(ref name) = args.flag_no_modules_global {
         b.no_modules_global(name);
     }
+    if let Some(ref name) = args.flag_out_name {
+        b.out_name(name);
+    }
+    if let Some(ref name) = args.flag_wasm_name {
+        b.wasm_name(name);
+    }
+    if let Some(ref path) = args.flag_wasm_import_path {
+        b.wasm_import_path(path);
+    }
 
     let out_dir = match args.flag_out_dir {
         Some(ref p) => p,
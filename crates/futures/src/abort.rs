@@ -0,0 +1,79 @@
+//! Cancelling JavaScript operations when their Rust future is dropped.
+
+use futures::prelude::*;
+use js_sys::Promise;
+use wasm_bindgen::prelude::*;
+
+use JsFuture;
+
+#[wasm_bindgen]
+extern {
+    type AbortController;
+    #[wasm_bindgen(constructor)]
+    fn new() -> AbortController;
+    #[wasm_bindgen(method, getter, structural)]
+    fn signal(this: &AbortController) -> JsValue;
+    #[wasm_bindgen(method, structural)]
+    fn abort(this: &AbortController);
+}
+
+/// A `JsFuture` for a JavaScript operation which is aborted when the future
+/// is dropped.
+///
+/// This is constructed with `AbortableJsFuture::new` and a function starting
+/// the operation, which is given an `AbortSignal` from a new
+/// `AbortController`. APIs like `fetch` accept such a signal to cancel the
+/// operation, typically rejecting their `Promise` with an `AbortError`.
+///
+/// If the future is dropped before the operation finishes the controller is
+/// aborted. Once the future has completed dropping it does nothing.
+///
+/// ```ignore
+/// let response = AbortableJsFuture::new(|signal| {
+///     let mut init = RequestInit::new();
+///     init.signal(Some(signal.unchecked_ref()));
+///     window.fetch_with_str_and_init("/data", &init)
+/// });
+/// ```
+pub struct AbortableJsFuture {
+    future: JsFuture,
+    controller: Option<AbortController>,
+}
+
+impl AbortableJsFuture {
+    /// Creates a new `AbortController` and calls `start` with its signal,
+    /// returning a future of the `Promise` it returns.
+    pub fn new<F>(start: F) -> AbortableJsFuture
+        where F: FnOnce(&JsValue) -> Promise,
+    {
+        let controller = AbortController::new();
+        let promise = start(&controller.signal());
+        AbortableJsFuture {
+            future: JsFuture::from(promise),
+            controller: Some(controller),
+        }
+    }
+}
+
+impl Future for AbortableJsFuture {
+    type Item = JsValue;
+    type Error = JsValue;
+
+    fn poll(&mut self) -> Poll<JsValue, JsValue> {
+        let result = self.future.poll();
+        match result {
+            Ok(Async::NotReady) => {}
+            // The operation has finished, so there's nothing to abort anymore.
+            Ok(Async::Ready(_)) | Err(_) => self.controller = None,
+        }
+        result
+    }
+}
+
+impl Drop for AbortableJsFuture {
+    fn drop(&mut self) {
+        if let Some(controller) = self.controller.take() {
+            controller.abort();
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Streams of events from JavaScript event targets.
+//!
+//! An `EventStream` registers an event listener on anything with the
+//! `addEventListener` and `removeEventListener` methods of the DOM's
+//! `EventTarget`, and yields the events it receives. The listener is removed
+//! again when the stream is dropped.
+//!
+//! ```ignore
+//! use wasm_bindgen_futures::events::{next_event, EventStream};
+//!
+//! let clicks = EventStream::new(&button, "click").for_each(|event| {
+//!     // ...
+//!     Ok(())
+//! });
+//! spawn_local(clicks);
+//!
+//! spawn_local(next_event(&image, "load").map(|_| {
+//!     // The image has loaded.
+//! }));
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use futures::prelude::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use timers::Shared;
+
+#[wasm_bindgen]
+extern {
+    type EventTarget;
+    #[wasm_bindgen(method, structural, js_name = addEventListener)]
+    fn add_event_listener(this: &EventTarget, event: &str, listener: &Closure<FnMut(JsValue)>);
+    #[wasm_bindgen(method, structural, js_name = removeEventListener)]
+    fn remove_event_listener(this: &EventTarget, event: &str, listener: &Closure<FnMut(JsValue)>);
+}
+
+/// A stream of the events of a given type dispatched to an event target.
+///
+/// The stream never ends, and yields every event dispatched since it was
+/// created, in order, even those dispatched while the stream isn't being
+/// polled. The event listener is removed when the stream is dropped.
+pub struct EventStream {
+    target: EventTarget,
+    event: String,
+    shared: Rc<RefCell<Shared<JsValue>>>,
+    listener: Closure<FnMut(JsValue)>,
+}
+
+impl EventStream {
+    /// Starts listening to `event` events dispatched to `target`.
+    ///
+    /// The `target` can be any object with `addEventListener` and
+    /// `removeEventListener` methods, like DOM elements, `window`, web
+    /// workers or `WebSocket`s.
+    pub fn new<T: JsCast>(target: &T, event: &str) -> EventStream {
+        let target = target.as_ref().clone().unchecked_into::<EventTarget>();
+        let shared = Shared::new();
+        let listener = {
+            let shared = shared.clone();
+            Closure::wrap(Box::new(move |event| {
+                Shared::push(&shared, event)
+            }) as Box<FnMut(JsValue)>)
+        };
+        target.add_event_listener(event, &listener);
+        EventStream {
+            target,
+            event: event.to_string(),
+            shared,
+            listener,
+        }
+    }
+}
+
+impl Stream for EventStream {
+    type Item = JsValue;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<JsValue>, ()> {
+        Ok(Shared::poll(&self.shared).map(Some))
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.target.remove_event_listener(&self.event, &self.listener);
+    }
+}
+
+/// A future of the next event of a given type dispatched to an event target,
+/// created with `next_event`.
+///
+/// The event listener is removed when the future is dropped.
+pub struct NextEvent {
+    events: EventStream,
+}
+
+/// Returns a future of the next `event` event dispatched to `target`.
+///
+/// This is the same as taking the first item of an `EventStream`, see
+/// `EventStream::new` for which targets are supported.
+pub fn next_event<T: JsCast>(target: &T, event: &str) -> NextEvent {
+    NextEvent {
+        events: EventStream::new(target, event),
+    }
+}
+
+impl Future for NextEvent {
+    type Item = JsValue;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<JsValue, ()> {
+        match self.events.poll()? {
+            Async::Ready(Some(event)) => Ok(Async::Ready(event)),
+            Async::Ready(None) => unreachable!("event streams never end"),
+            Async::NotReady => Ok(Async::NotReady),
+        }
+    }
+}
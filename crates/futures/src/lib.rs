@@ -7,7 +7,7 @@
 //! ability to interoperate with JavaScript events and JavaScript I/O
 //! primitives.
 //!
-//! There are two main interfaces in this crate currently:
+//! There are four main interfaces in this crate currently:
 //!
 //! 1. [**`JsFuture`**](./struct.JsFuture.html)
 //!
@@ -21,7 +21,18 @@
 //!    JavaScript `Promise`. The future's result will translate to either a
 //!    rejected or resolved `Promise` in JavaScript.
 //!
-//! These two items should provide enough of a bridge to interoperate the two
+//! 3. [**`spawn_local`**](./fn.spawn_local.html)
+//!
+//!    Runs a Rust `Future<Item = (), Error = ()>` in the background on the
+//!    current thread, without creating a JavaScript `Promise` for it.
+//!
+//! 4. [**`JsStream`**](./struct.JsStream.html) and
+//!    [**`stream_to_async_iterator`**](./fn.stream_to_async_iterator.html)
+//!
+//!    The equivalents of the above for JavaScript async iterators and Rust
+//!    `Stream`s, for values which are produced incrementally.
+//!
+//! These items should provide enough of a bridge to interoperate the two
 //! systems and make sure that Rust/JavaScript can work together with
 //! asynchronous and I/O work.
 //!
@@ -110,15 +121,24 @@ extern crate futures;
 extern crate wasm_bindgen;
 extern crate js_sys;
 
-use std::sync::Arc;
 use std::cell::{RefCell, Cell};
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
 
 use futures::executor::{self, Spawn, Notify};
 use futures::prelude::*;
-use futures::sync::oneshot;
-use js_sys::{Function, Promise};
+use futures::task::{self, Task};
+use js_sys::Promise;
 use wasm_bindgen::prelude::*;
 
+mod abort;
+mod stream;
+pub mod events;
+pub mod timers;
+
+pub use abort::AbortableJsFuture;
+pub use stream::{stream_to_async_iterator, JsStream};
+
 /// A Rust `Future` backed by a JavaScript `Promise`.
 ///
 /// This type is constructed with a JavaScript `Promise` object and translates
@@ -127,38 +147,77 @@ use wasm_bindgen::prelude::*;
 /// with the JavaScript `Promise`.
 ///
 /// Currently this type is constructed with `JsFuture::from`.
+///
+/// A `JsFuture` can be dropped before its `Promise` settles, in which case the
+/// callbacks registered with the `Promise` stay alive until it does, and the
+/// result is simply discarded. To also stop the underlying operation see
+/// `AbortableJsFuture`.
 pub struct JsFuture {
-    resolved: oneshot::Receiver<JsValue>,
-    rejected: oneshot::Receiver<JsValue>,
+    inner: Rc<RefCell<Inner>>,
+}
+
+// State shared between a `JsFuture` and the callbacks registered with its
+// `Promise`.
+struct Inner {
+    // The settled value of the promise, `Ok` if it was resolved and `Err` if it
+    // was rejected, until the future returns it.
+    result: Option<Result<JsValue, JsValue>>,
+
+    // The task waiting on the promise to settle, if any.
+    task: Option<Task>,
+
+    // The callbacks registered with the promise. These are kept alive here
+    // until one of them is invoked, even if the `JsFuture` has been dropped,
+    // as the promise could still settle at any point. This is a reference
+    // cycle through the callbacks, broken once the promise settles.
     callbacks: Option<(Closure<FnMut(JsValue)>, Closure<FnMut(JsValue)>)>,
 }
 
 impl From<Promise> for JsFuture {
     fn from(js: Promise) -> JsFuture {
         // Use the `then` method to schedule two callbacks, one for the
-        // resolved value and one for the rejected value. These two callbacks
-        // will be connected to oneshot channels which feed back into our
-        // future.
+        // resolved value and one for the rejected value. Both store the value
+        // in our shared state and wake up whoever's waiting on it.
         //
         // This may not be the speediest option today but it should work!
-        let (tx1, rx1) = oneshot::channel();
-        let (tx2, rx2) = oneshot::channel();
-        let mut tx1 = Some(tx1);
-        let resolve = Closure::wrap(Box::new(move |val| {
-            drop(tx1.take().unwrap().send(val));
-        }) as Box<FnMut(_)>);
-        let mut tx2 = Some(tx2);
-        let reject = Closure::wrap(Box::new(move |val| {
-            drop(tx2.take().unwrap().send(val));
-        }) as Box<FnMut(_)>);
+        let inner = Rc::new(RefCell::new(Inner {
+            result: None,
+            task: None,
+            callbacks: None,
+        }));
+
+        fn finish(inner: &RefCell<Inner>, val: Result<JsValue, JsValue>) {
+            let task = {
+                let mut inner = inner.borrow_mut();
+                // The promise settles at most once, so this is our chance to
+                // clean up the callbacks. Dropping a `Closure` while it's
+                // running is fine, it's freed once it returns.
+                drop(inner.callbacks.take());
+                inner.result = Some(val);
+                inner.task.take()
+            };
+            if let Some(task) = task {
+                task.notify();
+            }
+        }
+
+        let resolve = {
+            let inner = inner.clone();
+            Closure::wrap(Box::new(move |val| {
+                finish(&inner, Ok(val));
+            }) as Box<FnMut(_)>)
+        };
+        let reject = {
+            let inner = inner.clone();
+            Closure::wrap(Box::new(move |val| {
+                finish(&inner, Err(val));
+            }) as Box<FnMut(_)>)
+        };
 
         js.then2(&resolve, &reject);
+        inner.borrow_mut().callbacks = Some((resolve, reject));
 
-        JsFuture {
-            resolved: rx1,
-            rejected: rx2,
-            callbacks: Some((resolve, reject)),
-        }
+        JsFuture { inner }
     }
 }
 
@@ -167,19 +226,15 @@ impl Future for JsFuture {
     type Error = JsValue;
 
     fn poll(&mut self) -> Poll<JsValue, JsValue> {
-        // Test if either our resolved or rejected side is finished yet. Note
-        // that they will return errors if they're disconnected which can't
-        // happen until we drop the `callbacks` field, which doesn't happen
-        // till we're done, so we dont need to handle that.
-        if let Ok(Async::Ready(val)) = self.resolved.poll() {
-            drop(self.callbacks.take());
-            return Ok(val.into())
-        }
-        if let Ok(Async::Ready(val)) = self.rejected.poll() {
-            drop(self.callbacks.take());
-            return Err(val)
+        let mut inner = self.inner.borrow_mut();
+        match inner.result.take() {
+            Some(Ok(val)) => Ok(Async::Ready(val)),
+            Some(Err(val)) => Err(val),
+            None => {
+                inner.task = Some(task::current());
+                Ok(Async::NotReady)
+            }
         }
-        Ok(Async::NotReady)
     }
 }
 
@@ -206,17 +261,61 @@ impl Future for JsFuture {
 pub fn future_to_promise<F>(future: F) -> Promise
     where F: Future<Item = JsValue, Error = JsValue> + 'static,
 {
-    _future_to_promise(Box::new(future))
+    let mut future = Some(future);
+    Promise::new(&mut |resolve, reject| {
+        let future = future.take().unwrap().then(move |result| {
+            let (val, f) = match result {
+                Ok(value) => (value, resolve),
+                Err(value) => (value, reject),
+            };
+            drop(f.call1(&JsValue::undefined(), &val));
+            Ok::<(), ()>(())
+        });
+        spawn_local(future);
+    })
 }
 
-// Implementation of actually transforming a future into a JavaScript `Promise`.
-//
-// The only primitive we have to work with here is `Promise::new`, which gives
-// us two callbacks that we can use to either reject or resolve the promise.
-// It's our job to ensure that one of those callbacks is called at the
-// appropriate time.
+/// Runs a Rust `Future` on the current thread, in the background.
+///
+/// This is like `future_to_promise` for futures which have nothing to
+/// return, for example those kicked off by event handlers. No JavaScript
+/// `Promise` is allocated: the future is polled right away, and then again in
+/// a microtask each time its task is notified, until it completes. Its result
+/// is discarded.
+///
+/// # Panics
+///
+/// If the `future` provided panics then it's never polled again, and any
+/// resources it holds are leaked.
+pub fn spawn_local<F>(future: F)
+    where F: Future<Item = (), Error = ()> + 'static,
+{
+    _spawn_local(Box::new(future))
+}
+
+/// Runtime support for `#[wasm_bindgen]` exported functions which return
+/// futures. This is an internal module with no stability guarantees.
+#[doc(hidden)]
+pub mod __rt {
+    use futures::prelude::*;
+    use wasm_bindgen::prelude::*;
+
+    /// Converts the future returned by an exported function into the
+    /// `Promise` returned to JavaScript.
+    pub fn future_to_promise<F>(future: F) -> JsValue
+        where F: Future + 'static,
+              F::Item: Into<JsValue>,
+              F::Error: Into<JsValue>,
+    {
+        let future = future.map(|item| item.into()).map_err(|err| err.into());
+        super::future_to_promise(future).into()
+    }
+}
+
+// Implementation of actually executing a future in the background, shared by
+// `spawn_local` and `future_to_promise`.
 //
-// Now we know that JavaScript (in general) can't block and is largely
+// We know that JavaScript (in general) can't block and is largely
 // notification/callback driven. That means that our future must either have
 // synchronous computational work to do, or it's "scheduled a notification" to
 // happen. These notifications are likely callbacks to get executed when things
@@ -226,32 +325,34 @@ pub fn future_to_promise<F>(future: F) -> Promise
 //
 // This isn't necessarily the greatest future executor in the world, but it
 // should get the job done for now hopefully.
-fn _future_to_promise(future: Box<Future<Item = JsValue, Error = JsValue>>) -> Promise {
-    let mut future = Some(executor::spawn(future));
-    return Promise::new(&mut |resolve, reject| {
-        Package::poll(&Arc::new(Package {
-            spawn: RefCell::new(future.take().unwrap()),
-            resolve,
-            reject,
+fn _spawn_local(future: Box<Future<Item = (), Error = ()>>) {
+    let package = TASKS.with(|tasks| {
+        let id = tasks.next_id.get();
+        tasks.next_id.set(id.wrapping_add(1));
+        let package = Rc::new(Package {
+            id,
+            spawn: RefCell::new(executor::spawn(future)),
             notified: Cell::new(State::Notified),
-        }));
+        });
+        tasks.live.borrow_mut().insert(id, package.clone());
+        package
     });
+    Package::poll(&package);
 
     struct Package {
+        // The key of this future in `TASKS`, which is also the id passed to
+        // `Notifier` when it's notified.
+        id: usize,
+
         // Our "spawned future". This'll have everything we need to poll the
         // future and continue to move it forward.
-        spawn: RefCell<Spawn<Box<Future<Item = JsValue, Error = JsValue>>>>,
+        spawn: RefCell<Spawn<Box<Future<Item = (), Error = ()>>>>,
 
         // The current state of this future, expressed in an enum below. This
         // indicates whether we're currently polling the future, received a
         // notification and need to keep polling, or if we're waiting for a
         // notification to come in (and no one is polling).
         notified: Cell<State>,
-
-        // Our two callbacks connected to the `Promise` that we returned to
-        // JavaScript.  We'll be invoking one of these at the end.
-        resolve: Function,
-        reject: Function,
     }
 
     // The possible states our `Package` (future) can be in, tracked internally
@@ -269,23 +370,75 @@ fn _future_to_promise(future: Box<Future<Item = JsValue, Error = JsValue>>) -> P
         // being polled.
         Notified,
 
-        // The future is blocked, waiting for something to happen. Stored here
-        // is a self-reference to the future itself so we can pull it out in
-        // `Notify` and continue polling.
-        //
-        // Note that the self-reference here is an Arc-cycle that will leak
-        // memory unless the future completes, but currently that should be ok
-        // as we'll have to stick around anyway while the future is executing!
-        //
-        // This state is removed as soon as a notification comes in, so the leak
-        // should only be "temporary"
-        Waiting(Arc<Package>),
+        // The future is blocked, waiting for something to happen. When the
+        // notification comes in `Notifier` looks the future up in `TASKS` and
+        // continues polling it.
+        Waiting,
     }
 
-    // No shared memory right now, wasm is single threaded, no need to worry
-    // about this!
-    unsafe impl Send for Package {}
-    unsafe impl Sync for Package {}
+    // Every future which has been spawned and hasn't finished yet, keyed by
+    // its id.
+    //
+    // A future stays in here while it's waiting for a notification, so it
+    // will leak memory if it never completes, but currently that should be ok
+    // as we'll have to stick around anyway while the future is executing!
+    struct Tasks {
+        live: RefCell<HashMap<usize, Rc<Package>>>,
+        next_id: Cell<usize>,
+    }
+
+    thread_local!(static TASKS: Tasks = Tasks {
+        live: RefCell::new(HashMap::new()),
+        next_id: Cell::new(0),
+    });
+
+    // Futures which have been notified and are waiting to be polled again,
+    // in the order they were notified. The queue is drained in a microtask,
+    // which is scheduled whenever the first future is pushed onto an empty
+    // queue, so all wakeups which happen in one turn of the event loop are
+    // processed in one batch.
+    struct RunQueue {
+        tasks: RefCell<VecDeque<Rc<Package>>>,
+        scheduled: Cell<bool>,
+        run: Closure<FnMut(JsValue)>,
+    }
+
+    thread_local!(static QUEUE: RunQueue = RunQueue {
+        tasks: RefCell::new(VecDeque::new()),
+        scheduled: Cell::new(false),
+        run: Closure::wrap(Box::new(|_| RunQueue::run()) as Box<FnMut(JsValue)>),
+    });
+
+    impl RunQueue {
+        // Enqueues `task` to be polled, scheduling the queue to run if it
+        // isn't already.
+        fn push(task: Rc<Package>) {
+            QUEUE.with(|queue| {
+                queue.tasks.borrow_mut().push_back(task);
+                if !queue.scheduled.replace(true) {
+                    Promise::resolve(&JsValue::undefined()).then(&queue.run);
+                }
+            });
+        }
+
+        // Polls every queued task, including those which are notified while
+        // we're running.
+        //
+        // The queue isn't borrowed while a task is polled as that may push
+        // more tasks. We also clear `scheduled` up front, so if a task panics
+        // the tasks after it are still run by the microtask scheduled by the
+        // next push.
+        fn run() {
+            QUEUE.with(|queue| queue.scheduled.set(false));
+            loop {
+                let task = QUEUE.with(|queue| queue.tasks.borrow_mut().pop_front());
+                match task {
+                    Some(task) => Package::poll(&task),
+                    None => break,
+                }
+            }
+        }
+    }
 
     impl Package {
         // Move the future contained in `me` as far forward as we can. This will
@@ -297,7 +450,7 @@ fn _future_to_promise(future: Box<Future<Item = JsValue, Error = JsValue>>) -> P
         //       as it can starve other computations. Rather it should instead
         //       yield every so often with something like `setTimeout` with the
         //       timeout set to zero.
-        fn poll(me: &Arc<Package>) {
+        fn poll(me: &Rc<Package>) {
             loop {
                 match me.notified.replace(State::Polling) {
                     // We received a notification while previously polling, or
@@ -312,36 +465,50 @@ fn _future_to_promise(future: Box<Future<Item = JsValue, Error = JsValue>>) -> P
                     // When the notification comes in it'll notify our task, see
                     // our `Waiting` state, and resume the polling process
                     State::Polling => {
-                        me.notified.set(State::Waiting(me.clone()));
+                        me.notified.set(State::Waiting);
                         break
                     }
 
-                    State::Waiting(_) => panic!("shouldn't see waiting state!"),
+                    State::Waiting => panic!("shouldn't see waiting state!"),
                 }
 
-                let (val, f) = match me.spawn.borrow_mut().poll_future_notify(me, 0) {
-                    // If the future is ready, immediately call the
-                    // resolve/reject callback and then return as we're done.
-                    Ok(Async::Ready(value)) => (value, &me.resolve),
-                    Err(value) => (value, &me.reject),
+                let notifier: &'static Notifier = &NOTIFIER;
+                match me.spawn.borrow_mut().poll_future_notify(&notifier, me.id) {
+                    // If the future is finished we're done, and there's
+                    // nothing left to do with its result.
+                    Ok(Async::Ready(())) | Err(()) => {
+                        TASKS.with(|tasks| tasks.live.borrow_mut().remove(&me.id));
+                        break
+                    }
 
                     // Otherwise keep going in our loop, if we weren't notified
                     // we'll break out and start waiting.
                     Ok(Async::NotReady) => continue,
-                };
-
-                drop(f.call1(&JsValue::undefined(), &val));
-                break
+                }
             }
         }
     }
 
-    impl Notify for Package {
-        fn notify(&self, _id: usize) {
-            match self.notified.replace(State::Notified) {
-                // we need to schedule polling to resume, so we do so
-                // immediately for now
-                State::Waiting(me) => Package::poll(&me),
+    // The handle futures are notified through. `Notify` has to be `Send` and
+    // `Sync`, which `Package` isn't, so this only carries the future's id and
+    // looks it up in the current thread's `TASKS`.
+    struct Notifier;
+
+    static NOTIFIER: Notifier = Notifier;
+
+    impl Notify for Notifier {
+        fn notify(&self, id: usize) {
+            let me = match TASKS.with(|tasks| tasks.live.borrow().get(&id).cloned()) {
+                Some(me) => me,
+                // the future has already finished
+                None => return,
+            };
+            match me.notified.replace(State::Notified) {
+                // we need to schedule polling to resume, which happens in a
+                // microtask rather than right here as we may be deep inside
+                // some other code's stack, like a JS callback which is in the
+                // middle of borrowing something the future also uses.
+                State::Waiting => RunQueue::push(me),
 
                 // we were already notified, and were just notified again;
                 // having now coalesced the notifications we return as it's
@@ -354,7 +521,6 @@ fn _future_to_promise(future: Box<Future<Item = JsValue, Error = JsValue>>) -> P
                 // polling process later sees this notification and will
                 // continue polling. For us, though, there's nothing else to do,
                 // so we bail out.
-                // later see
                 State::Polling => {}
             }
         }
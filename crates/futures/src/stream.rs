@@ -0,0 +1,271 @@
+//! Converting between JavaScript async iterators and Rust `Stream`s.
+
+use std::cell::RefCell;
+use std::mem;
+use std::rc::Rc;
+
+use futures::future;
+use futures::prelude::*;
+use futures::unsync::oneshot;
+use js_sys::{Function, Object, Promise, Reflect, Symbol, TypeError};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use {future_to_promise, JsFuture};
+
+#[wasm_bindgen]
+extern {
+    #[wasm_bindgen(js_namespace = Reflect, js_name = set)]
+    fn set_method(target: &Object, key: &JsValue, method: &Closure<FnMut() -> JsValue>);
+}
+
+/// A Rust `Stream` backed by a JavaScript async iterator.
+///
+/// This type is constructed with `JsStream::new` from any object implementing
+/// the async iteration protocol, that is with a `Symbol.asyncIterator` method,
+/// like async generators. A `ReadableStream` which isn't async iterable in the
+/// current browser is read through the reader from its `getReader` method
+/// instead.
+///
+/// Each value produced by the iterator is an item of the stream, and the
+/// stream ends once the iterator is done. If the iterator rejects the stream
+/// fails with the rejection value, and ends afterwards.
+///
+/// Dropping the stream calls the iterator's `return` method, if it has one,
+/// or releases the lock on a `ReadableStream` read through a reader.
+pub struct JsStream {
+    iterator: JsValue,
+    next: Function,
+    release: Option<Function>,
+    pending: Option<JsFuture>,
+    done: bool,
+}
+
+impl JsStream {
+    /// Creates a stream of the values produced by the async iterable
+    /// `iterable`.
+    ///
+    /// Returns an error if `iterable` isn't async iterable or a
+    /// `ReadableStream`, or if creating its iterator throws.
+    pub fn new(iterable: &JsValue) -> Result<JsStream, JsValue> {
+        let (iterator, next, release) = if let Some(f) = method(iterable, &Symbol::async_iterator().into()) {
+            let iterator = f.call0(iterable)?;
+            let release = method(&iterator, &JsValue::from("return"));
+            match method(&iterator, &JsValue::from("next")) {
+                Some(next) => (iterator, next, release),
+                None => return Err(TypeError::new("async iterator has no `next` method").into()),
+            }
+        } else if let Some(f) = method(iterable, &JsValue::from("getReader")) {
+            let reader = f.call0(iterable)?;
+            let release = method(&reader, &JsValue::from("releaseLock"));
+            match method(&reader, &JsValue::from("read")) {
+                Some(read) => (reader, read, release),
+                None => return Err(TypeError::new("stream reader has no `read` method").into()),
+            }
+        } else {
+            return Err(TypeError::new("value is not async iterable").into())
+        };
+
+        Ok(JsStream {
+            iterator,
+            next,
+            release,
+            pending: None,
+            done: false,
+        })
+    }
+}
+
+impl Stream for JsStream {
+    type Item = JsValue;
+    type Error = JsValue;
+
+    fn poll(&mut self) -> Poll<Option<JsValue>, JsValue> {
+        if self.done {
+            return Ok(Async::Ready(None))
+        }
+
+        // Ask the iterator for its next value if we haven't already. Both
+        // async iterators and stream readers return a promise of an object
+        // with `value` and `done` properties, but we resolve whatever we get
+        // into a promise to be lenient.
+        if self.pending.is_none() {
+            match self.next.call0(&self.iterator) {
+                Ok(result) => {
+                    self.pending = Some(JsFuture::from(Promise::resolve(&result)));
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Err(e)
+                }
+            }
+        }
+
+        let result = match self.pending.as_mut().unwrap().poll() {
+            Ok(Async::Ready(result)) => result,
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Err(e) => {
+                self.pending = None;
+                self.done = true;
+                return Err(e)
+            }
+        };
+        self.pending = None;
+
+        if Reflect::get(&result, &JsValue::from("done")).as_bool() == Some(true) {
+            self.done = true;
+            return Ok(Async::Ready(None))
+        }
+        Ok(Async::Ready(Some(Reflect::get(&result, &JsValue::from("value")))))
+    }
+}
+
+impl Drop for JsStream {
+    fn drop(&mut self) {
+        // Let the iterator clean up, or unlock the `ReadableStream` so it can
+        // be read by someone else. Errors are ignored as there's no one left
+        // to report them to, for example a reader may refuse to release its
+        // lock while a read is still pending.
+        //
+        // An iterator's `return` method returns a promise, and if that's
+        // rejected we catch it so it isn't reported as an unhandled rejection.
+        if let Some(release) = self.release.take() {
+            if let Ok(ret) = release.call0(&self.iterator) {
+                if ret.is_object() {
+                    IGNORE.with(|ignore| Promise::resolve(&ret).catch(ignore));
+                }
+            }
+        }
+    }
+}
+
+thread_local!(static IGNORE: Closure<FnMut(JsValue)> = {
+    Closure::wrap(Box::new(|_| {}) as Box<FnMut(JsValue)>)
+});
+
+/// Returns the method `key` of `target`, if it has one.
+fn method(target: &JsValue, key: &JsValue) -> Option<Function> {
+    if !target.is_object() && !target.is_function() {
+        return None
+    }
+    let f = Reflect::get(target, key);
+    if f.is_function() {
+        Some(f.unchecked_into())
+    } else {
+        None
+    }
+}
+
+/// Converts a Rust `Stream` into a JavaScript async iterator.
+///
+/// The returned object implements the async iteration protocol so it can be
+/// consumed with `for await...of` in JavaScript. Each call to its `next`
+/// method returns a `Promise` of the stream's next item, with the stream
+/// polled in the background like `future_to_promise` does with futures. The
+/// promise is rejected if the stream fails, after which the iterator is done.
+/// Calls to `next` made before earlier ones have resolved are answered in the
+/// order they were made.
+///
+/// The stream is dropped as soon as it ends or fails, or when the iterator's
+/// `return` method is called, as `for await...of` does when the loop is left
+/// early. Calling `return` also frees the closures backing the iterator's
+/// methods, after which the iterator can't be used anymore. Otherwise they're
+/// leaked.
+pub fn stream_to_async_iterator<S>(stream: S) -> Object
+    where S: Stream<Item = JsValue, Error = JsValue> + 'static,
+{
+    _stream_to_async_iterator(Box::new(stream))
+}
+
+// State shared by the calls to `next` of an iterator returned from
+// `stream_to_async_iterator`.
+struct AsyncIterator {
+    // The stream we're iterating over, or `None` once it's finished.
+    stream: Option<Box<Stream<Item = JsValue, Error = JsValue>>>,
+
+    // Resolves once the latest call to `next` has been answered, which the
+    // next call waits for before polling the stream itself.
+    last: Option<oneshot::Receiver<()>>,
+
+    // The closures backing the iterator's methods, which are freed when its
+    // `return` method is called.
+    closures: Vec<Closure<FnMut() -> JsValue>>,
+}
+
+fn _stream_to_async_iterator(stream: Box<Stream<Item = JsValue, Error = JsValue>>) -> Object {
+    let state = Rc::new(RefCell::new(AsyncIterator {
+        stream: Some(stream),
+        last: None,
+        closures: Vec::new(),
+    }));
+
+    let next_state = state.clone();
+    let next = Closure::wrap(Box::new(move || {
+        let (tx, rx) = oneshot::channel::<()>();
+        let previous = mem::replace(&mut next_state.borrow_mut().last, Some(rx));
+        let previous: Box<Future<Item = (), Error = JsValue>> = match previous {
+            Some(rx) => Box::new(rx.then(|_| Ok::<(), JsValue>(()))),
+            None => Box::new(future::ok::<(), JsValue>(())),
+        };
+
+        let state = next_state.clone();
+        let item = previous
+            .and_then(move |()| future::poll_fn(move || {
+                let mut state = state.borrow_mut();
+                let result = match state.stream {
+                    Some(ref mut stream) => stream.poll(),
+                    None => return Ok(Async::Ready(None)),
+                };
+                match result {
+                    Ok(Async::NotReady) | Ok(Async::Ready(Some(_))) => {}
+                    Ok(Async::Ready(None)) | Err(_) => state.stream = None,
+                }
+                result
+            }))
+            .then(move |result| {
+                // Dropping the sender lets the next call to `next` proceed.
+                drop(tx);
+                result.map(iterator_result)
+            });
+        future_to_promise(item).into()
+    }) as Box<FnMut() -> JsValue>);
+
+    let iterator = Object::new();
+    let this = iterator.clone();
+    let async_iterator = Closure::wrap(Box::new(move || {
+        JsValue::from(this.clone())
+    }) as Box<FnMut() -> JsValue>);
+
+    let return_state = state.clone();
+    let return_ = Closure::wrap(Box::new(move || {
+        let closures = {
+            let mut state = return_state.borrow_mut();
+            state.stream = None;
+            mem::replace(&mut state.closures, Vec::new())
+        };
+        // We're running inside one of the closures, so they're only dropped
+        // once we've returned, in a later microtask.
+        let done = JsFuture::from(Promise::resolve(&JsValue::undefined()))
+            .map(move |_| {
+                drop(closures);
+                iterator_result(None)
+            });
+        future_to_promise(done).into()
+    }) as Box<FnMut() -> JsValue>);
+
+    set_method(&iterator, &JsValue::from("next"), &next);
+    set_method(&iterator, &JsValue::from("return"), &return_);
+    set_method(&iterator, &Symbol::async_iterator().into(), &async_iterator);
+    state.borrow_mut().closures.extend(vec![next, return_, async_iterator]);
+    iterator
+}
+
+/// Creates the object an async iterator's `next` method resolves to.
+fn iterator_result(item: Option<JsValue>) -> JsValue {
+    let result = Object::new();
+    let done = item.is_none();
+    let value = item.unwrap_or(JsValue::undefined());
+    Reflect::set(result.as_ref(), &JsValue::from("value"), &value);
+    Reflect::set(result.as_ref(), &JsValue::from("done"), &JsValue::from(done));
+    result.into()
+}
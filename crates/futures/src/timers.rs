@@ -0,0 +1,247 @@
+//! Futures and streams driven by JavaScript timers.
+//!
+//! These are thin wrappers around `setTimeout`, `setInterval` and
+//! `requestAnimationFrame`, which clear their timer when they're dropped.
+//! `Delay` and `Interval` work anywhere those globals are defined, like
+//! browsers, web workers and node, while `AnimationFrames` needs a browser.
+//!
+//! ```ignore
+//! use std::time::Duration;
+//! use wasm_bindgen_futures::spawn_local;
+//! use wasm_bindgen_futures::timers::Delay;
+//!
+//! spawn_local(Delay::new(Duration::from_millis(500)).map(|()| {
+//!     // Half a second later...
+//! }));
+//! ```
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::prelude::*;
+use futures::task::{self, Task};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(closure: &Closure<FnMut()>, timeout_ms: u32) -> JsValue;
+    #[wasm_bindgen(js_name = clearTimeout)]
+    fn clear_timeout(id: &JsValue);
+    #[wasm_bindgen(js_name = setInterval)]
+    fn set_interval(closure: &Closure<FnMut()>, timeout_ms: u32) -> JsValue;
+    #[wasm_bindgen(js_name = clearInterval)]
+    fn clear_interval(id: &JsValue);
+    #[wasm_bindgen(js_name = requestAnimationFrame)]
+    fn request_animation_frame(closure: &Closure<FnMut(f64)>) -> u32;
+    #[wasm_bindgen(js_name = cancelAnimationFrame)]
+    fn cancel_animation_frame(id: u32);
+}
+
+/// A future which completes after a given duration, using `setTimeout`.
+///
+/// The timeout is cleared if the future is dropped before it fires.
+pub struct Delay {
+    id: JsValue,
+    shared: Rc<RefCell<Shared<()>>>,
+    _closure: Closure<FnMut()>,
+}
+
+impl Delay {
+    /// Creates a future which completes once `duration` has passed.
+    ///
+    /// Like `setTimeout`, the duration is rounded down to milliseconds and the
+    /// future may complete later than requested, but never earlier.
+    pub fn new(duration: Duration) -> Delay {
+        let shared = Shared::new();
+        let closure = {
+            let shared = shared.clone();
+            Closure::wrap(Box::new(move || Shared::push(&shared, ())) as Box<FnMut()>)
+        };
+        Delay {
+            id: set_timeout(&closure, millis(duration)),
+            shared,
+            _closure: closure,
+        }
+    }
+}
+
+impl Future for Delay {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        Ok(Shared::poll(&self.shared))
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        clear_timeout(&self.id);
+    }
+}
+
+/// A stream which yields repeatedly at a fixed interval, using
+/// `setInterval`.
+///
+/// The stream never ends, and yields once for every time the interval fired
+/// since it was last polled. The interval is cleared when it's dropped.
+pub struct Interval {
+    id: JsValue,
+    shared: Rc<RefCell<Shared<()>>>,
+    _closure: Closure<FnMut()>,
+}
+
+impl Interval {
+    /// Creates a stream yielding every `period`, starting one `period` from
+    /// now.
+    pub fn new(period: Duration) -> Interval {
+        let shared = Shared::new();
+        let closure = {
+            let shared = shared.clone();
+            Closure::wrap(Box::new(move || Shared::push(&shared, ())) as Box<FnMut()>)
+        };
+        Interval {
+            id: set_interval(&closure, millis(period)),
+            shared,
+            _closure: closure,
+        }
+    }
+}
+
+impl Stream for Interval {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<()>, ()> {
+        Ok(Shared::poll(&self.shared).map(Some))
+    }
+}
+
+impl Drop for Interval {
+    fn drop(&mut self) {
+        clear_interval(&self.id);
+    }
+}
+
+/// A stream of animation frames, using `requestAnimationFrame`.
+///
+/// Each item is the timestamp passed to the `requestAnimationFrame` callback,
+/// in milliseconds. A frame is only requested while the stream is being
+/// polled, so frames which happen while the consumer is busy are skipped
+/// rather than buffered. Any requested frame is cancelled when the stream is
+/// dropped.
+///
+/// This is only available in browsers.
+pub struct AnimationFrames {
+    id: Option<u32>,
+    shared: Rc<RefCell<Shared<f64>>>,
+    closure: Closure<FnMut(f64)>,
+}
+
+impl AnimationFrames {
+    /// Creates a new stream of animation frames.
+    pub fn new() -> AnimationFrames {
+        let shared = Shared::new();
+        let closure = {
+            let shared = shared.clone();
+            Closure::wrap(Box::new(move |timestamp| {
+                Shared::push(&shared, timestamp)
+            }) as Box<FnMut(f64)>)
+        };
+        AnimationFrames {
+            id: None,
+            shared,
+            closure,
+        }
+    }
+}
+
+impl Default for AnimationFrames {
+    fn default() -> AnimationFrames {
+        AnimationFrames::new()
+    }
+}
+
+impl Stream for AnimationFrames {
+    type Item = f64;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<f64>, ()> {
+        if let Async::Ready(timestamp) = Shared::poll(&self.shared) {
+            self.id = None;
+            return Ok(Async::Ready(Some(timestamp)))
+        }
+        if self.id.is_none() {
+            self.id = Some(request_animation_frame(&self.closure));
+        }
+        Ok(Async::NotReady)
+    }
+}
+
+impl Drop for AnimationFrames {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            cancel_animation_frame(id);
+        }
+    }
+}
+
+// State shared between a timer and its JS callback. This is also used for
+// event listeners in the `events` module.
+pub(crate) struct Shared<T> {
+    // Values passed to the callback which haven't been returned by the timer
+    // yet, in the order they came in.
+    ready: VecDeque<T>,
+
+    // The task to notify when a value comes in, if any.
+    task: Option<Task>,
+}
+
+impl<T> Shared<T> {
+    pub(crate) fn new() -> Rc<RefCell<Shared<T>>> {
+        Rc::new(RefCell::new(Shared {
+            ready: VecDeque::new(),
+            task: None,
+        }))
+    }
+
+    // Called from JS callbacks to hand a value to the timer.
+    pub(crate) fn push(me: &Rc<RefCell<Shared<T>>>, value: T) {
+        let task = {
+            let mut me = me.borrow_mut();
+            me.ready.push_back(value);
+            me.task.take()
+        };
+        if let Some(task) = task {
+            task.notify();
+        }
+    }
+
+    // Returns the next value handed to the timer, or arranges for the current
+    // task to be notified when there is one.
+    pub(crate) fn poll(me: &Rc<RefCell<Shared<T>>>) -> Async<T> {
+        let mut me = me.borrow_mut();
+        match me.ready.pop_front() {
+            Some(value) => Async::Ready(value),
+            None => {
+                me.task = Some(task::current());
+                Async::NotReady
+            }
+        }
+    }
+}
+
+/// Converts `duration` to whole milliseconds, saturating at `u32::MAX`.
+fn millis(duration: Duration) -> u32 {
+    let ms = duration.as_secs()
+        .saturating_mul(1000)
+        .saturating_add(u64::from(duration.subsec_millis()));
+    if ms > u64::from(u32::max_value()) {
+        u32::max_value()
+    } else {
+        ms as u32
+    }
+}
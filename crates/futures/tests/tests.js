@@ -0,0 +1,148 @@
+const assert = require('assert');
+const wasm = require('wasm-bindgen-test');
+
+exports.call_exports_returning_futures = function() {
+  const add = wasm.async_add(1, 2);
+  assert.ok(add instanceof Promise);
+  return add
+    .then(sum => {
+      assert.strictEqual(sum, 3);
+      return wasm.async_fail('oh no');
+    })
+    .then(() => {
+      throw new Error('async_fail should reject');
+    }, err => {
+      assert.strictEqual(err, 'oh no');
+    });
+};
+
+exports.async_generator = async function*(values) {
+  for (const value of values)
+    yield value;
+};
+
+exports.failing_async_generator = async function*() {
+  yield 1;
+  throw 'oh no';
+};
+
+exports.TrackedIterable = class {
+  constructor() {
+    this.returned = false;
+  }
+
+  [Symbol.asyncIterator]() {
+    return {
+      next: async () => ({ value: 1, done: false }),
+      return: async () => {
+        this.returned = true;
+        return { done: true };
+      },
+    };
+  }
+};
+
+exports.RejectingReturnIterable = class {
+  constructor() {
+    this.returned = false;
+  }
+
+  [Symbol.asyncIterator]() {
+    return {
+      next: async () => ({ value: 1, done: false }),
+      return: async () => {
+        this.returned = true;
+        throw new Error('return failed');
+      },
+    };
+  }
+};
+
+exports.TrackedReadable = class {
+  constructor() {
+    this.released = false;
+  }
+
+  getReader() {
+    return {
+      read: async () => ({ value: 1, done: false }),
+      releaseLock: () => this.released = true,
+    };
+  }
+};
+
+exports.collect_async_iterator = async function(iter) {
+  const values = [];
+  for await (const value of iter)
+    values.push(value);
+  return values;
+};
+
+exports.first_of_async_iterator = async function(iter) {
+  for await (const value of iter)
+    return value;
+};
+
+let PENDING_RESOLVE = null;
+
+exports.pending_promise = function() {
+  return new Promise(resolve => PENDING_RESOLVE = resolve);
+};
+
+exports.settle_pending_promise = function() {
+  PENDING_RESOLVE(42);
+  // Give the promise's callbacks a chance to run before the test finishes.
+  return new Promise(resolve => setTimeout(resolve, 0));
+};
+
+// Older versions of node don't have `AbortController`, so provide just enough
+// of one for these tests.
+if (typeof AbortController === 'undefined') {
+  global.AbortController = class {
+    constructor() {
+      this.signal = { aborted: false };
+    }
+    abort() {
+      this.signal.aborted = true;
+    }
+  };
+}
+
+let LAST_SIGNAL = null;
+
+exports.abortable_operation = function(signal) {
+  LAST_SIGNAL = signal;
+  return new Promise(() => {});
+};
+
+exports.last_signal_aborted = function() {
+  return LAST_SIGNAL !== null && LAST_SIGNAL.aborted;
+};
+
+// Just enough of the DOM's `EventTarget` to test `EventStream` in node.
+exports.FakeTarget = class {
+  constructor() {
+    this.listeners = {};
+  }
+
+  addEventListener(event, listener) {
+    this.listeners[event] = this.listeners[event] || [];
+    this.listeners[event].push(listener);
+  }
+
+  removeEventListener(event, listener) {
+    const listeners = this.listeners[event] || [];
+    const i = listeners.indexOf(listener);
+    if (i !== -1)
+      listeners.splice(i, 1);
+  }
+
+  dispatch(event, value) {
+    for (const listener of (this.listeners[event] || []).slice())
+      listener(value);
+  }
+
+  listenerCount(event) {
+    return (this.listeners[event] || []).length;
+  }
+};
@@ -7,11 +7,69 @@ extern crate wasm_bindgen;
 extern crate wasm_bindgen_futures;
 extern crate wasm_bindgen_test;
 
-use futures::Future;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::{Future, Stream};
+use futures::unsync::oneshot;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use wasm_bindgen_futures::{future_to_promise, spawn_local, stream_to_async_iterator};
+use wasm_bindgen_futures::{AbortableJsFuture, JsFuture, JsStream};
+use wasm_bindgen_futures::events::{next_event, EventStream};
+use wasm_bindgen_futures::timers::{Delay, Interval};
 use wasm_bindgen_test::*;
 
+#[wasm_bindgen(module = "tests/tests.js")]
+extern {
+    fn call_exports_returning_futures() -> js_sys::Promise;
+    fn async_generator(values: &js_sys::Array) -> JsValue;
+    fn failing_async_generator() -> JsValue;
+    fn collect_async_iterator(iter: &js_sys::Object) -> js_sys::Promise;
+    fn first_of_async_iterator(iter: &js_sys::Object) -> js_sys::Promise;
+    fn pending_promise() -> js_sys::Promise;
+    fn settle_pending_promise() -> js_sys::Promise;
+    fn abortable_operation(signal: &JsValue) -> js_sys::Promise;
+    fn last_signal_aborted() -> bool;
+
+    type TrackedIterable;
+    #[wasm_bindgen(constructor)]
+    fn new() -> TrackedIterable;
+    #[wasm_bindgen(method, getter, structural)]
+    fn returned(this: &TrackedIterable) -> bool;
+
+    type RejectingReturnIterable;
+    #[wasm_bindgen(constructor)]
+    fn new() -> RejectingReturnIterable;
+    #[wasm_bindgen(method, getter, structural)]
+    fn returned(this: &RejectingReturnIterable) -> bool;
+
+    type TrackedReadable;
+    #[wasm_bindgen(constructor)]
+    fn new() -> TrackedReadable;
+    #[wasm_bindgen(method, getter, structural)]
+    fn released(this: &TrackedReadable) -> bool;
+
+    type FakeTarget;
+    #[wasm_bindgen(constructor)]
+    fn new() -> FakeTarget;
+    #[wasm_bindgen(method)]
+    fn dispatch(this: &FakeTarget, event: &str, value: &JsValue);
+    #[wasm_bindgen(method, js_name = listenerCount)]
+    fn listener_count(this: &FakeTarget, event: &str) -> u32;
+}
+
+#[wasm_bindgen]
+pub fn async_add(a: u32, b: u32) -> impl Future<Item = u32, Error = JsValue> {
+    let p = js_sys::Promise::resolve(&JsValue::from(a));
+    JsFuture::from(p).map(move |a| a.as_f64().unwrap() as u32 + b)
+}
+
+#[wasm_bindgen]
+pub fn async_fail(msg: &str) -> impl Future<Item = u32, Error = String> {
+    futures::future::err(msg.to_string())
+}
+
 #[wasm_bindgen_test(async)]
 fn promise_resolve_is_ok_future() -> impl Future<Item = (), Error = JsValue> {
     let p = js_sys::Promise::resolve(&JsValue::from(42));
@@ -49,3 +107,285 @@ fn error_future_is_rejected_promise() -> impl Future<Item = (), Error = JsValue>
         Ok(())
     })
 }
+
+#[wasm_bindgen_test(async)]
+fn spawn_local_runs_future() -> impl Future<Item = (), Error = JsValue> {
+    let (tx, rx) = oneshot::channel();
+    let p = js_sys::Promise::resolve(&JsValue::from(42));
+    spawn_local(JsFuture::from(p).map(|x| {
+        assert_eq!(x, 42);
+        tx.send(()).unwrap();
+    }).map_err(|_| unreachable!()));
+    rx.map_err(|_| unreachable!())
+}
+
+#[wasm_bindgen_test(async)]
+fn notify_does_not_poll_synchronously() -> impl Future<Item = (), Error = JsValue> {
+    let (tx, rx) = oneshot::channel();
+    let (done_tx, done_rx) = oneshot::channel();
+    let ran = Rc::new(Cell::new(false));
+    let ran2 = ran.clone();
+    spawn_local(rx.map(move |()| {
+        ran2.set(true);
+        done_tx.send(()).unwrap();
+    }).map_err(|_| unreachable!()));
+
+    // The future is only polled again once we've returned to the event loop.
+    tx.send(()).unwrap();
+    assert!(!ran.get());
+    done_rx
+        .map(move |()| assert!(ran.get()))
+        .map_err(|_| unreachable!())
+}
+
+#[wasm_bindgen_test(async)]
+fn notify_while_borrowed() -> impl Future<Item = (), Error = JsValue> {
+    let (tx, rx) = oneshot::channel();
+    let (done_tx, done_rx) = oneshot::channel();
+    let state = Rc::new(RefCell::new(Some(tx)));
+    let state2 = state.clone();
+    spawn_local(rx.map(move |()| {
+        assert!(state2.borrow().is_none());
+        done_tx.send(()).unwrap();
+    }).map_err(|_| unreachable!()));
+
+    // Waking the future while we hold a borrow which it also needs mustn't
+    // poll it in the middle of our borrow.
+    let mut borrow = state.borrow_mut();
+    borrow.take().unwrap().send(()).unwrap();
+    drop(borrow);
+    done_rx.map_err(|_| unreachable!())
+}
+
+#[wasm_bindgen_test(async)]
+fn wakeups_run_in_notification_order() -> impl Future<Item = (), Error = JsValue> {
+    let order = Rc::new(RefCell::new(Vec::new()));
+    let mut senders = Vec::new();
+    let mut done = Vec::new();
+    for i in 0..3 {
+        let (tx, rx) = oneshot::channel();
+        let (done_tx, done_rx) = oneshot::channel();
+        let order = order.clone();
+        spawn_local(rx.map(move |()| {
+            order.borrow_mut().push(i);
+            done_tx.send(()).unwrap();
+        }).map_err(|_| unreachable!()));
+        senders.push(tx);
+        done.push(done_rx);
+    }
+
+    for tx in senders.into_iter().rev() {
+        tx.send(()).unwrap();
+    }
+    futures::future::join_all(done)
+        .map(move |_| assert_eq!(*order.borrow(), [2, 1, 0]))
+        .map_err(|_| unreachable!())
+}
+
+#[wasm_bindgen_test(async)]
+fn exported_futures_return_promises() -> impl Future<Item = (), Error = JsValue> {
+    JsFuture::from(call_exports_returning_futures()).map(|_| ())
+}
+
+#[wasm_bindgen_test(async)]
+fn async_iterator_is_stream() -> impl Future<Item = (), Error = JsValue> {
+    let values = js_sys::Array::new();
+    values.push(&JsValue::from(1));
+    values.push(&JsValue::from(2));
+    values.push(&JsValue::from(3));
+    JsStream::new(&async_generator(&values))
+        .unwrap()
+        .collect()
+        .map(|values| {
+            assert_eq!(values, [1, 2, 3]);
+        })
+}
+
+#[wasm_bindgen_test(async)]
+fn async_iterator_rejection_is_stream_error() -> impl Future<Item = (), Error = JsValue> {
+    JsStream::new(&failing_async_generator())
+        .unwrap()
+        .into_future()
+        .map_err(|(e, _)| e)
+        .and_then(|(first, stream)| {
+            assert_eq!(first.unwrap(), 1);
+            stream.into_future().map_err(|(e, _)| e)
+        })
+        .then(|result| {
+            let err = result.err().unwrap();
+            assert_eq!(err, "oh no");
+            Ok(())
+        })
+}
+
+#[wasm_bindgen_test]
+fn non_iterable_is_not_stream() {
+    assert!(JsStream::new(&JsValue::from(1)).is_err());
+    assert!(JsStream::new(&js_sys::Object::new().into()).is_err());
+}
+
+#[wasm_bindgen_test]
+fn dropped_stream_returns_iterator() {
+    let iterable = TrackedIterable::new();
+    let stream = JsStream::new(iterable.as_ref()).unwrap();
+    assert!(!iterable.returned());
+    drop(stream);
+    assert!(iterable.returned());
+}
+
+#[wasm_bindgen_test(async)]
+fn dropped_stream_catches_rejected_return() -> impl Future<Item = (), Error = JsValue> {
+    let iterable = RejectingReturnIterable::new();
+    drop(JsStream::new(iterable.as_ref()).unwrap());
+    assert!(iterable.returned());
+    // Give the rejection a chance to be reported as unhandled.
+    Delay::new(Duration::from_millis(10)).map_err(|()| unreachable!())
+}
+
+#[wasm_bindgen_test]
+fn dropped_stream_releases_reader() {
+    let readable = TrackedReadable::new();
+    let stream = JsStream::new(readable.as_ref()).unwrap();
+    assert!(!readable.released());
+    drop(stream);
+    assert!(readable.released());
+}
+
+#[wasm_bindgen_test(async)]
+fn stream_is_async_iterator() -> impl Future<Item = (), Error = JsValue> {
+    let stream = futures::stream::iter_ok((1..4).map(JsValue::from));
+    let iter = stream_to_async_iterator(stream);
+    JsFuture::from(collect_async_iterator(&iter)).map(|values| {
+        let values = js_sys::Array::from(&values);
+        assert_eq!(values.length(), 3);
+        assert_eq!(String::from(values.join(",")), "1,2,3");
+    })
+}
+
+// A stream which records when it's dropped.
+struct DropFlag<S> {
+    stream: S,
+    dropped: Rc<Cell<bool>>,
+}
+
+impl<S: Stream> Stream for DropFlag<S> {
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> futures::Poll<Option<S::Item>, S::Error> {
+        self.stream.poll()
+    }
+}
+
+impl<S> Drop for DropFlag<S> {
+    fn drop(&mut self) {
+        self.dropped.set(true);
+    }
+}
+
+#[wasm_bindgen_test(async)]
+fn breaking_out_of_async_iterator_drops_stream() -> impl Future<Item = (), Error = JsValue> {
+    let dropped = Rc::new(Cell::new(false));
+    let stream = DropFlag {
+        stream: futures::stream::repeat(JsValue::from(1)),
+        dropped: dropped.clone(),
+    };
+    let iter = stream_to_async_iterator(stream);
+    JsFuture::from(first_of_async_iterator(&iter)).map(move |value| {
+        assert_eq!(value, 1);
+        assert!(dropped.get());
+    })
+}
+
+#[wasm_bindgen_test(async)]
+fn dropped_future_settles_later() -> impl Future<Item = (), Error = JsValue> {
+    drop(JsFuture::from(pending_promise()));
+    // The promise settling after the future was dropped must not invoke a
+    // freed callback.
+    JsFuture::from(settle_pending_promise()).map(|_| ())
+}
+
+#[wasm_bindgen_test]
+fn dropped_abortable_future_aborts() {
+    let future = AbortableJsFuture::new(|signal| abortable_operation(signal));
+    assert!(!last_signal_aborted());
+    drop(future);
+    assert!(last_signal_aborted());
+}
+
+#[wasm_bindgen_test(async)]
+fn completed_abortable_future_does_not_abort() -> impl Future<Item = (), Error = JsValue> {
+    let future = AbortableJsFuture::new(|signal| {
+        abortable_operation(signal);
+        js_sys::Promise::resolve(&JsValue::from(42))
+    });
+    future
+        .map(|x| {
+            assert_eq!(x, 42);
+            assert!(!last_signal_aborted());
+        })
+}
+
+#[wasm_bindgen_test(async)]
+fn delay_completes() -> impl Future<Item = (), Error = JsValue> {
+    let start = js_sys::Date::now();
+    Delay::new(Duration::from_millis(10))
+        .map(move |()| assert!(js_sys::Date::now() - start >= 9.0))
+        .map_err(|()| unreachable!())
+}
+
+#[wasm_bindgen_test(async)]
+fn dropped_delay_never_fires() -> impl Future<Item = (), Error = JsValue> {
+    let fired = Rc::new(Cell::new(false));
+    let fired2 = fired.clone();
+    let delay = Delay::new(Duration::from_millis(0)).map(move |()| fired2.set(true));
+    spawn_local(delay.select(futures::future::ok(())).map(|_| ()).map_err(|_| ()));
+    Delay::new(Duration::from_millis(10))
+        .map(move |()| assert!(!fired.get()))
+        .map_err(|()| unreachable!())
+}
+
+#[wasm_bindgen_test(async)]
+fn interval_yields_repeatedly() -> impl Future<Item = (), Error = JsValue> {
+    Interval::new(Duration::from_millis(1))
+        .take(3)
+        .collect()
+        .map(|ticks| assert_eq!(ticks.len(), 3))
+        .map_err(|()| unreachable!())
+}
+
+#[wasm_bindgen_test(async)]
+fn event_stream_yields_events() -> impl Future<Item = (), Error = JsValue> {
+    let target = FakeTarget::new();
+    let events = EventStream::new(&target, "ping");
+    assert_eq!(target.listener_count("ping"), 1);
+    target.dispatch("ping", &JsValue::from(1));
+    target.dispatch("pong", &JsValue::from(2));
+    target.dispatch("ping", &JsValue::from(3));
+    events
+        .take(2)
+        .collect()
+        .map(|events| assert_eq!(events, [1, 3]))
+        .map_err(|()| unreachable!())
+}
+
+#[wasm_bindgen_test]
+fn dropped_event_stream_removes_listener() {
+    let target = FakeTarget::new();
+    let events = EventStream::new(&target, "ping");
+    let next = next_event(&target, "ping");
+    assert_eq!(target.listener_count("ping"), 2);
+    drop(events);
+    drop(next);
+    assert_eq!(target.listener_count("ping"), 0);
+}
+
+#[wasm_bindgen_test(async)]
+fn next_event_is_first_event() -> impl Future<Item = (), Error = JsValue> {
+    let target = FakeTarget::new();
+    let next = next_event(&target, "ping");
+    target.dispatch("ping", &JsValue::from(1));
+    target.dispatch("ping", &JsValue::from(2));
+    next.map(|event| assert_eq!(event, 1))
+        .map_err(|()| unreachable!())
+}
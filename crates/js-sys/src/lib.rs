@@ -2843,19 +2843,347 @@ extern "C" {
     pub fn delete(this: &WeakSet, value: &Object) -> bool;
 }
 
-// WebAssembly
-#[wasm_bindgen]
-extern "C" {
-    #[derive(Clone, Debug)]
-    pub type WebAssembly;
+#[allow(non_snake_case)]
+pub mod WebAssembly {
+    use super::*;
 
-    /// The `WebAssembly.validate()` function validates a given typed
-    /// array of WebAssembly binary code, returning whether the bytes
-    /// form a valid wasm module (`true`) or not (`false`).
-    ///
-    /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/validate
-    #[wasm_bindgen(static_method_of = WebAssembly, catch)]
-    pub fn validate(bufferSource: &JsValue) -> Result<bool, JsValue>;
+    // WebAssembly
+    #[wasm_bindgen]
+    extern "C" {
+        /// The `WebAssembly.compile()` function compiles a `WebAssembly.Module`
+        /// from WebAssembly binary code. This function is useful if it is
+        /// necessary to a compile a module before it can be instantiated
+        /// (otherwise, the `WebAssembly.instantiate()` function should be used).
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/compile
+        #[wasm_bindgen(js_namespace = WebAssembly)]
+        pub fn compile(buffer_source: &JsValue) -> Promise;
+
+        /// The `WebAssembly.instantiate()` function allows you to compile and
+        /// instantiate WebAssembly code.
+        ///
+        /// This overload takes the WebAssembly binary code and returns a
+        /// `Promise` of an object with both the compiled `module` and its
+        /// first `instance`.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/instantiate
+        #[wasm_bindgen(js_namespace = WebAssembly, js_name = instantiate)]
+        pub fn instantiate_buffer(buffer: &[u8], imports: &Object) -> Promise;
+
+        /// The `WebAssembly.instantiate()` function allows you to compile and
+        /// instantiate WebAssembly code.
+        ///
+        /// This overload takes an already-compiled `WebAssembly.Module` and
+        /// returns a `Promise` of a new `Instance` of it.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/instantiate
+        #[wasm_bindgen(js_namespace = WebAssembly, js_name = instantiate)]
+        pub fn instantiate_module(module: &Module, imports: &Object) -> Promise;
+
+        /// The `WebAssembly.instantiateStreaming()` function compiles and
+        /// instantiates a WebAssembly module directly from a streamed
+        /// underlying source. This is the most efficient, optimized way to
+        /// load wasm code.
+        ///
+        /// The `response` is a `Promise` of a `Response`, like the one
+        /// returned by `fetch`.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/instantiateStreaming
+        #[wasm_bindgen(js_namespace = WebAssembly, js_name = instantiateStreaming)]
+        pub fn instantiate_streaming(response: &Promise, imports: &Object) -> Promise;
+
+        /// The `WebAssembly.validate()` function validates a given typed
+        /// array of WebAssembly binary code, returning whether the bytes
+        /// form a valid wasm module (`true`) or not (`false`).
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/validate
+        #[wasm_bindgen(js_namespace = WebAssembly, catch)]
+        pub fn validate(buffer_source: &JsValue) -> Result<bool, JsValue>;
+    }
+
+    // WebAssembly.CompileError
+    #[wasm_bindgen]
+    extern "C" {
+        /// The `WebAssembly.CompileError()` constructor creates a new
+        /// WebAssembly `CompileError` object, which indicates an error during
+        /// WebAssembly decoding or validation.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/CompileError
+        #[wasm_bindgen(extends = Error, js_namespace = WebAssembly)]
+        #[derive(Clone, Debug)]
+        pub type CompileError;
+
+        /// The `WebAssembly.CompileError()` constructor creates a new
+        /// WebAssembly `CompileError` object, which indicates an error during
+        /// WebAssembly decoding or validation.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/CompileError
+        #[wasm_bindgen(constructor, js_namespace = WebAssembly)]
+        pub fn new(message: &str) -> CompileError;
+    }
+
+    // WebAssembly.LinkError
+    #[wasm_bindgen]
+    extern "C" {
+        /// The `WebAssembly.LinkError()` constructor creates a new WebAssembly
+        /// `LinkError` object, which indicates an error during module
+        /// instantiation (besides traps from the start function).
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/LinkError
+        #[wasm_bindgen(extends = Error, js_namespace = WebAssembly)]
+        #[derive(Clone, Debug)]
+        pub type LinkError;
+
+        /// The `WebAssembly.LinkError()` constructor creates a new WebAssembly
+        /// `LinkError` object, which indicates an error during module
+        /// instantiation (besides traps from the start function).
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/LinkError
+        #[wasm_bindgen(constructor, js_namespace = WebAssembly)]
+        pub fn new(message: &str) -> LinkError;
+    }
+
+    // WebAssembly.RuntimeError
+    #[wasm_bindgen]
+    extern "C" {
+        /// The `WebAssembly.RuntimeError()` constructor creates a new
+        /// WebAssembly `RuntimeError` object, the type that is thrown whenever
+        /// WebAssembly specifies a trap.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/RuntimeError
+        #[wasm_bindgen(extends = Error, js_namespace = WebAssembly)]
+        #[derive(Clone, Debug)]
+        pub type RuntimeError;
+
+        /// The `WebAssembly.RuntimeError()` constructor creates a new
+        /// WebAssembly `RuntimeError` object, the type that is thrown whenever
+        /// WebAssembly specifies a trap.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/RuntimeError
+        #[wasm_bindgen(constructor, js_namespace = WebAssembly)]
+        pub fn new(message: &str) -> RuntimeError;
+    }
+
+    // WebAssembly.Module
+    #[wasm_bindgen]
+    extern "C" {
+        /// A `WebAssembly.Module` object contains stateless WebAssembly code
+        /// that has already been compiled by the browser and can be
+        /// efficiently shared with Workers, and instantiated multiple times.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Module
+        #[wasm_bindgen(js_namespace = WebAssembly, extends = Object)]
+        #[derive(Clone, Debug)]
+        pub type Module;
+
+        /// The `WebAssembly.Module()` constructor synchronously compiles
+        /// WebAssembly binary code into a `WebAssembly.Module`.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Module
+        #[wasm_bindgen(constructor, js_namespace = WebAssembly, catch)]
+        pub fn new(buffer_source: &JsValue) -> Result<Module, JsValue>;
+
+        /// The `WebAssembly.Module()` constructor synchronously compiles
+        /// WebAssembly binary code into a `WebAssembly.Module`.
+        ///
+        /// This overload takes the binary code as a slice of bytes, which is
+        /// copied by the constructor.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Module
+        #[wasm_bindgen(constructor, js_namespace = WebAssembly, catch)]
+        pub fn new_with_bytes(bytes: &[u8]) -> Result<Module, JsValue>;
+
+        /// The `WebAssembly.customSections()` function returns a copy of the
+        /// contents of all custom sections in the given module with the given
+        /// string name.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Module/customSections
+        #[wasm_bindgen(static_method_of = Module, js_namespace = WebAssembly, js_name = customSections)]
+        pub fn custom_sections(module: &Module, section_name: &str) -> Array;
+
+        /// The `WebAssembly.exports()` function returns an array containing
+        /// descriptions of all the declared exports of the given `Module`.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Module/exports
+        #[wasm_bindgen(static_method_of = Module, js_namespace = WebAssembly)]
+        pub fn exports(module: &Module) -> Array;
+
+        /// The `WebAssembly.imports()` function returns an array containing
+        /// descriptions of all the declared imports of the given `Module`.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Module/imports
+        #[wasm_bindgen(static_method_of = Module, js_namespace = WebAssembly)]
+        pub fn imports(module: &Module) -> Array;
+    }
+
+    // WebAssembly.Instance
+    #[wasm_bindgen]
+    extern "C" {
+        /// A `WebAssembly.Instance` object is a stateful, executable instance
+        /// of a `WebAssembly.Module`. Instance objects contain all the exported
+        /// WebAssembly functions that allow calling into WebAssembly code from
+        /// JavaScript.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Instance
+        #[wasm_bindgen(js_namespace = WebAssembly, extends = Object)]
+        #[derive(Clone, Debug)]
+        pub type Instance;
+
+        /// The `WebAssembly.Instance()` constructor function can be called to
+        /// synchronously instantiate a given `WebAssembly.Module`
+        /// object. However, the primary way to get an `Instance` is through the
+        /// asynchronous `WebAssembly.instantiate()` function.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Instance
+        #[wasm_bindgen(constructor, js_namespace = WebAssembly, catch)]
+        pub fn new(module: &Module, imports: &Object) -> Result<Instance, JsValue>;
+
+        /// The `exports` readonly property of the `WebAssembly.Instance` object
+        /// prototype returns an object containing as its members all the
+        /// functions exported from the WebAssembly module instance, to allow
+        /// them to be accessed and used by JavaScript.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Instance/exports
+        #[wasm_bindgen(method, getter, js_namespace = WebAssembly)]
+        pub fn exports(this: &Instance) -> Object;
+    }
+
+    // WebAssembly.Memory
+    #[wasm_bindgen]
+    extern "C" {
+        /// The `WebAssembly.Memory()` constructor creates a new `Memory` object
+        /// which is a resizable `ArrayBuffer` that holds the raw bytes of
+        /// memory accessed by a WebAssembly `Instance`.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Memory
+        #[wasm_bindgen(js_namespace = WebAssembly, extends = Object)]
+        #[derive(Clone, Debug)]
+        pub type Memory;
+
+        /// The `WebAssembly.Memory()` constructor creates a new `Memory` object
+        /// which is a resizable `ArrayBuffer` that holds the raw bytes of
+        /// memory accessed by a WebAssembly `Instance`.
+        ///
+        /// The `descriptor` has an `initial` and optionally a `maximum` size,
+        /// in units of WebAssembly pages (64KiB each).
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Memory
+        #[wasm_bindgen(constructor, js_namespace = WebAssembly, catch)]
+        pub fn new(descriptor: &Object) -> Result<Memory, JsValue>;
+
+        /// The `buffer` prototype property of the `WebAssembly.Memory` object
+        /// returns the buffer contained in the memory.
+        ///
+        /// This is an `ArrayBuffer`, or a `SharedArrayBuffer` for shared
+        /// memories.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Memory/buffer
+        #[wasm_bindgen(method, getter, js_namespace = WebAssembly)]
+        pub fn buffer(this: &Memory) -> JsValue;
+
+        /// The `grow()` protoype method of the `Memory` object increases the
+        /// size of the memory instance by a specified number of WebAssembly
+        /// pages, returning the previous size in pages.
+        ///
+        /// Takes the number of pages to grow (64KiB in size) and returns the
+        /// previous size of memory, in pages. Throws a `RangeError` if the
+        /// memory can't grow that much.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Memory/grow
+        #[wasm_bindgen(method, js_namespace = WebAssembly, catch)]
+        pub fn grow(this: &Memory, pages: u32) -> Result<u32, JsValue>;
+    }
+
+    // WebAssembly.Table
+    #[wasm_bindgen]
+    extern "C" {
+        /// The `WebAssembly.Table()` constructor creates a new `Table` object
+        /// of the given size and element type.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Table
+        #[wasm_bindgen(js_namespace = WebAssembly, extends = Object)]
+        #[derive(Clone, Debug)]
+        pub type Table;
+
+        /// The `WebAssembly.Table()` constructor creates a new `Table` object
+        /// of the given size and element type.
+        ///
+        /// The `descriptor` has an `element` type, currently always
+        /// `"anyfunc"`, an `initial` size and optionally a `maximum` size.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Table
+        #[wasm_bindgen(constructor, js_namespace = WebAssembly, catch)]
+        pub fn new(descriptor: &Object) -> Result<Table, JsValue>;
+
+        /// The `length` prototype property of the `WebAssembly.Table` object
+        /// returns the length of the table, i.e. the number of elements in the
+        /// table.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Table/length
+        #[wasm_bindgen(method, getter, js_namespace = WebAssembly)]
+        pub fn length(this: &Table) -> u32;
+
+        /// The `get()` prototype method of the `WebAssembly.Table()` object
+        /// retrieves a function reference stored at a given index, or `null`
+        /// if there isn't one.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Table/get
+        #[wasm_bindgen(method, js_namespace = WebAssembly, catch)]
+        pub fn get(this: &Table, index: u32) -> Result<JsValue, JsValue>;
+
+        /// The `grow()` prototype method of the `WebAssembly.Table` object
+        /// increases the size of the `Table` instance by a specified number of
+        /// elements, returning the previous length.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Table/grow
+        #[wasm_bindgen(method, js_namespace = WebAssembly, catch)]
+        pub fn grow(this: &Table, additional_capacity: u32) -> Result<u32, JsValue>;
+
+        /// The `set()` prototype method of the `WebAssembly.Table` object
+        /// mutates a reference stored at a given index to a different value.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Table/set
+        #[wasm_bindgen(method, js_namespace = WebAssembly, catch)]
+        pub fn set(this: &Table, index: u32, function: &Function) -> Result<(), JsValue>;
+    }
+
+    // WebAssembly.Global
+    #[wasm_bindgen]
+    extern "C" {
+        /// A `WebAssembly.Global` object represents a global variable
+        /// instance, accessible from both JavaScript and importable/exportable
+        /// across one or more `WebAssembly.Module` instances.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Global
+        #[wasm_bindgen(js_namespace = WebAssembly, extends = Object)]
+        #[derive(Clone, Debug)]
+        pub type Global;
+
+        /// The `WebAssembly.Global()` constructor creates a new `Global`
+        /// object representing a global variable instance.
+        ///
+        /// The `descriptor` has the `value` type of the global, like `"i32"`
+        /// or `"f64"`, and whether it's `mutable`.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Global
+        #[wasm_bindgen(constructor, js_namespace = WebAssembly, catch)]
+        pub fn new(descriptor: &Object, value: &JsValue) -> Result<Global, JsValue>;
+
+        /// The `value` prototype property of the `WebAssembly.Global` object
+        /// returns the value contained inside the global.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Global/value
+        #[wasm_bindgen(method, getter, js_namespace = WebAssembly)]
+        pub fn value(this: &Global) -> JsValue;
+
+        /// The `value` prototype property of the `WebAssembly.Global` object
+        /// sets the value contained inside the global. This throws if the
+        /// global isn't mutable.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WebAssembly/Global/value
+        #[wasm_bindgen(method, setter = value, js_namespace = WebAssembly, catch)]
+        pub fn set_value(this: &Global, value: &JsValue) -> Result<(), JsValue>;
+    }
 }
 
 // JSON
@@ -3208,6 +3536,13 @@ extern "C" {
     #[derive(Clone, Debug)]
     pub type Symbol;
 
+    /// The `Symbol.asyncIterator` well-known symbol specifies the default
+    /// async iterator for an object. Used by `for await...of`.
+    ///
+    /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Symbol/asyncIterator
+    #[wasm_bindgen(static_method_of = Symbol, getter, structural, js_name = asyncIterator)]
+    pub fn async_iterator() -> Symbol;
+
     /// The `Symbol.hasInstance` well-known symbol is used to determine
     /// if a constructor object recognizes an object as its instance.
     /// The `instanceof` operator's behavior can be customized by this symbol.
@@ -3342,8 +3677,8 @@ pub mod Intl {
         /// will be validated as structurally valid language tags.
         ///
         /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Intl/getCanonicalLocales
-        #[wasm_bindgen(js_name = getCanonicalLocales, js_namespace = Intl)]
-        pub fn get_canonical_locales(s: &JsValue) -> Array;
+        #[wasm_bindgen(catch, js_name = getCanonicalLocales, js_namespace = Intl)]
+        pub fn get_canonical_locales(s: &JsValue) -> Result<Array, JsValue>;
     }
 
     // Intl.Collator
@@ -3361,8 +3696,8 @@ pub mod Intl {
         /// that enable language sensitive string comparison.
         ///
         /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Collator
-        #[wasm_bindgen(constructor, js_namespace = Intl)]
-        pub fn new(locales: &Array, options: &Object) -> Collator;
+        #[wasm_bindgen(constructor, js_namespace = Intl, catch)]
+        pub fn new(locales: &Array, options: &Object) -> Result<Collator, JsValue>;
 
         /// The Intl.Collator.prototype.compare property returns a function that
         /// compares two strings according to the sort order of this Collator
@@ -3386,8 +3721,8 @@ pub mod Intl {
         /// locale.
         ///
         /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Collator/supportedLocalesOf
-        #[wasm_bindgen(static_method_of = Collator, js_namespace = Intl, js_name = supportedLocalesOf)]
-        pub fn supported_locales_of(locales: &Array, options: &Object) -> Array;
+        #[wasm_bindgen(static_method_of = Collator, js_namespace = Intl, js_name = supportedLocalesOf, catch)]
+        pub fn supported_locales_of(locales: &Array, options: &Object) -> Result<Array, JsValue>;
     }
 
     // Intl.DateTimeFormat
@@ -3405,8 +3740,8 @@ pub mod Intl {
         /// that enable language-sensitive date and time formatting.
         ///
         /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/DateTimeFormat
-        #[wasm_bindgen(constructor, js_namespace = Intl)]
-        pub fn new(locales: &Array, options: &Object) -> DateTimeFormat;
+        #[wasm_bindgen(constructor, js_namespace = Intl, catch)]
+        pub fn new(locales: &Array, options: &Object) -> Result<DateTimeFormat, JsValue>;
 
         /// The Intl.DateTimeFormat.prototype.format property returns a getter function that
         /// formats a date according to the locale and formatting options of this
@@ -3437,8 +3772,100 @@ pub mod Intl {
         /// locale.
         ///
         /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/DateTimeFormat/supportedLocalesOf
-        #[wasm_bindgen(static_method_of = DateTimeFormat, js_namespace = Intl, js_name = supportedLocalesOf)]
-        pub fn supported_locales_of(locales: &Array, options: &Object) -> Array;
+        #[wasm_bindgen(static_method_of = DateTimeFormat, js_namespace = Intl, js_name = supportedLocalesOf, catch)]
+        pub fn supported_locales_of(locales: &Array, options: &Object) -> Result<Array, JsValue>;
+    }
+
+    // Intl.NumberFormat
+    #[wasm_bindgen]
+    extern "C" {
+        /// The Intl.NumberFormat object is a constructor for objects
+        /// that enable language sensitive number formatting.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/NumberFormat
+        #[wasm_bindgen(js_namespace = Intl)]
+        #[derive(Clone, Debug)]
+        pub type NumberFormat;
+
+        /// The Intl.NumberFormat object is a constructor for objects
+        /// that enable language sensitive number formatting.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/NumberFormat
+        #[wasm_bindgen(constructor, js_namespace = Intl, catch)]
+        pub fn new(locales: &Array, options: &Object) -> Result<NumberFormat, JsValue>;
+
+        /// The Intl.NumberFormat.prototype.format property returns a getter function that
+        /// formats a number according to the locale and formatting options of this
+        /// NumberFormat object.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/NumberFormat/format
+        #[wasm_bindgen(method, getter, js_class = "Intl.NumberFormat")]
+        pub fn format(this: &NumberFormat) -> Function;
+
+        /// The Intl.NumberFormat.prototype.formatToParts() method allows locale-aware
+        /// formatting of strings produced by NumberFormat formatters.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/NumberFormat/formatToParts
+        #[wasm_bindgen(method, js_class = "Intl.NumberFormat", js_name = formatToParts)]
+        pub fn format_to_parts(this: &NumberFormat, number: f64) -> Array;
+
+        /// The Intl.NumberFormat.prototype.resolvedOptions() method returns a new
+        /// object with properties reflecting the locale and number formatting
+        /// options computed during initialization of this NumberFormat object.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/NumberFormat/resolvedOptions
+        #[wasm_bindgen(method, js_namespace = Intl, js_name = resolvedOptions)]
+        pub fn resolved_options(this: &NumberFormat) -> Object;
+
+        /// The Intl.NumberFormat.supportedLocalesOf() method returns an array
+        /// containing those of the provided locales that are supported in number
+        /// formatting without having to fall back to the runtime's default locale.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/NumberFormat/supportedLocalesOf
+        #[wasm_bindgen(static_method_of = NumberFormat, js_namespace = Intl, js_name = supportedLocalesOf, catch)]
+        pub fn supported_locales_of(locales: &Array, options: &Object) -> Result<Array, JsValue>;
+    }
+
+    // Intl.PluralRules
+    #[wasm_bindgen]
+    extern "C" {
+        /// The Intl.PluralRules object is a constructor for objects
+        /// that enable plural sensitive formatting and plural language rules.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/PluralRules
+        #[wasm_bindgen(js_namespace = Intl)]
+        #[derive(Clone, Debug)]
+        pub type PluralRules;
+
+        /// The Intl.PluralRules object is a constructor for objects
+        /// that enable plural sensitive formatting and plural language rules.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/PluralRules
+        #[wasm_bindgen(constructor, js_namespace = Intl, catch)]
+        pub fn new(locales: &Array, options: &Object) -> Result<PluralRules, JsValue>;
+
+        /// The Intl.PluralRules.prototype.resolvedOptions() method returns a new
+        /// object with properties reflecting the locale and plural formatting
+        /// options computed during initialization of this PluralRules object.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/PluralRules/resolvedOptions
+        #[wasm_bindgen(method, js_namespace = Intl, js_name = resolvedOptions)]
+        pub fn resolved_options(this: &PluralRules) -> Object;
+
+        /// The Intl.PluralRules.prototype.select method returns a String indicating
+        /// which plural rule to use for locale-aware formatting.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/PluralRules/select
+        #[wasm_bindgen(method, js_namespace = Intl)]
+        pub fn select(this: &PluralRules, number: f64) -> JsString;
+
+        /// The Intl.PluralRules.supportedLocalesOf() method returns an array
+        /// containing those of the provided locales that are supported in plural
+        /// formatting without having to fall back to the runtime's default locale.
+        ///
+        /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/PluralRules/supportedLocalesOf
+        #[wasm_bindgen(static_method_of = PluralRules, js_namespace = Intl, js_name = supportedLocalesOf, catch)]
+        pub fn supported_locales_of(locales: &Array, options: &Object) -> Result<Array, JsValue>;
     }
 }
 
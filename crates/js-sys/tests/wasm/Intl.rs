@@ -8,7 +8,7 @@ fn get_canonical_locales() {
     locales.push(&"EN-US".into());
     locales.push(&"Fr".into());
     let locales = JsValue::from(locales);
-    let canonical_locales = Intl::get_canonical_locales(&locales);
+    let canonical_locales = Intl::get_canonical_locales(&locales).unwrap();
     assert_eq!(canonical_locales.length(), 2);
     canonical_locales.for_each(&mut |l, i, _| {
         if i == 0 {
@@ -17,7 +17,7 @@ fn get_canonical_locales() {
             assert_eq!(l, "fr");
         }
     });
-    let canonical_locales = Intl::get_canonical_locales(&"EN-US".into());
+    let canonical_locales = Intl::get_canonical_locales(&"EN-US".into()).unwrap();
     assert_eq!(canonical_locales.length(), 1);
     canonical_locales.for_each(&mut |l, _, _| {
         assert_eq!(l, "en-US");
@@ -29,11 +29,11 @@ fn collator() {
     let locales = Array::of1(&JsValue::from("en-US"));
     let opts = Object::new();
 
-    let c = Intl::Collator::new(&locales, &opts);
+    let c = Intl::Collator::new(&locales, &opts).unwrap();
     assert!(c.compare().is_instance_of::<Function>());
     assert!(c.resolved_options().is_instance_of::<Object>());
 
-    let a = Intl::Collator::supported_locales_of(&locales, &opts);
+    let a = Intl::Collator::supported_locales_of(&locales, &opts).unwrap();
     assert!(a.is_instance_of::<Array>());
 }
 
@@ -43,11 +43,59 @@ fn date_time_format() {
     let opts = Object::new();
     let epoch = Date::new(&JsValue::from(0));
 
-    let c = Intl::DateTimeFormat::new(&locales, &opts);
+    let c = Intl::DateTimeFormat::new(&locales, &opts).unwrap();
     assert!(c.format().is_instance_of::<Function>());
     assert!(c.format_to_parts(&epoch).is_instance_of::<Array>());
     assert!(c.resolved_options().is_instance_of::<Object>());
 
-    let a = Intl::DateTimeFormat::supported_locales_of(&locales, &opts);
+    let a = Intl::DateTimeFormat::supported_locales_of(&locales, &opts).unwrap();
+    assert!(a.is_instance_of::<Array>());
+}
+
+#[wasm_bindgen_test]
+fn number_format() {
+    let locales = Array::of1(&JsValue::from("en-US"));
+    let opts = Object::new();
+
+    let n = Intl::NumberFormat::new(&locales, &opts).unwrap();
+    let format = n.format();
+    assert!(format.is_instance_of::<Function>());
+    assert_eq!(format.call1(&JsValue::undefined(), &1234.5.into()).unwrap(), "1,234.5");
+    let mut formatted = String::new();
+    n.format_to_parts(1234.5).for_each(&mut |part, _, _| {
+        formatted.push_str(&Reflect::get(&part, &"value".into()).as_string().unwrap());
+    });
+    assert_eq!(formatted, "1,234.5");
+    assert!(n.resolved_options().is_instance_of::<Object>());
+
+    let a = Intl::NumberFormat::supported_locales_of(&locales, &opts).unwrap();
+    assert!(a.is_instance_of::<Array>());
+}
+
+#[wasm_bindgen_test]
+fn invalid_locales() {
+    let locales = Array::of1(&JsValue::from("not a locale"));
+    let opts = Object::new();
+
+    assert!(Intl::get_canonical_locales(&locales.clone().into()).is_err());
+    assert!(Intl::Collator::new(&locales, &opts).is_err());
+    assert!(Intl::DateTimeFormat::new(&locales, &opts).is_err());
+    assert!(Intl::NumberFormat::new(&locales, &opts).is_err());
+    assert!(Intl::PluralRules::new(&locales, &opts).is_err());
+    assert!(Intl::NumberFormat::supported_locales_of(&locales, &opts).is_err());
+    assert!(Intl::PluralRules::supported_locales_of(&locales, &opts).is_err());
+}
+
+#[wasm_bindgen_test]
+fn plural_rules() {
+    let locales = Array::of1(&JsValue::from("en-US"));
+    let opts = Object::new();
+
+    let r = Intl::PluralRules::new(&locales, &opts).unwrap();
+    assert!(r.resolved_options().is_instance_of::<Object>());
+    assert_eq!(JsValue::from(r.select(1.0)), "one");
+    assert_eq!(JsValue::from(r.select(2.0)), "other");
+
+    let a = Intl::PluralRules::supported_locales_of(&locales, &opts).unwrap();
     assert!(a.is_instance_of::<Array>());
 }
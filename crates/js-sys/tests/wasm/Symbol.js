@@ -1,5 +1,10 @@
 const assert = require('assert');
 
+exports.test_async_iterator = function(sym) {
+  assert.equal(typeof sym, "symbol");
+  assert.strictEqual(sym, Symbol.asyncIterator);
+};
+
 exports.test_has_instance = function(sym) {
   class Array1 {
     static [sym](instance) {
@@ -4,6 +4,7 @@ use js_sys::*;
 
 #[wasm_bindgen(module = "tests/wasm/Symbol.js")]
 extern {
+    fn test_async_iterator(sym: &Symbol);
     fn test_has_instance(sym: &Symbol);
     fn test_is_concat_spreadable(sym: &Symbol);
     fn test_iterator(sym: &Symbol);
@@ -22,6 +23,11 @@ extern {
     fn gensym(val: JsValue) -> Symbol;
 }
 
+#[wasm_bindgen_test]
+fn async_iterator() {
+    test_async_iterator(&Symbol::async_iterator());
+}
+
 #[wasm_bindgen_test]
 fn has_instance() {
     test_has_instance(&Symbol::has_instance());
@@ -0,0 +1,9 @@
+exports.streaming_supported = function() {
+  return typeof WebAssembly.instantiateStreaming === 'function' &&
+    typeof Response === 'function';
+};
+
+exports.wasm_response = function(bytes) {
+  const headers = { 'Content-Type': 'application/wasm' };
+  return Promise.resolve(new Response(bytes, { headers }));
+};
@@ -1,9 +1,181 @@
+use futures::future;
+use futures::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
 use wasm_bindgen_test::*;
 use js_sys::*;
 
+#[wasm_bindgen(module = "tests/wasm/WebAssembly.js")]
+extern {
+    fn streaming_supported() -> bool;
+    fn wasm_response(bytes: &JsValue) -> Promise;
+}
+
+// A module exporting a function `ans` returning 42, with a custom section
+// named `foo` holding four bytes.
+const MODULE: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+    0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f,
+    0x03, 0x02, 0x01, 0x00,
+    0x07, 0x07, 0x01, 0x03, b'a', b'n', b's', 0x00, 0x00,
+    0x0a, 0x06, 0x01, 0x04, 0x00, 0x41, 0x2a, 0x0b,
+    0x00, 0x08, 0x03, b'f', b'o', b'o', 0x01, 0x02, 0x03, 0x04,
+];
+
+fn module_bytes() -> JsValue {
+    let bytes = Array::new();
+    for byte in MODULE {
+        bytes.push(&JsValue::from(*byte));
+    }
+    Uint8Array::new(&bytes.into()).into()
+}
+
+fn descriptor(entries: &[(&str, JsValue)]) -> Object {
+    let descriptor = Object::new();
+    for (key, value) in entries {
+        Reflect::set(descriptor.as_ref(), &JsValue::from(*key), value);
+    }
+    descriptor
+}
+
 #[wasm_bindgen_test]
 fn validate() {
     assert!(!WebAssembly::validate(&ArrayBuffer::new(42).into()).unwrap());
+    assert!(WebAssembly::validate(&module_bytes()).unwrap());
 
     assert!(WebAssembly::validate(&2.into()).is_err());
 }
+
+#[wasm_bindgen_test(async)]
+fn compile() -> impl Future<Item = (), Error = JsValue> {
+    JsFuture::from(WebAssembly::compile(&module_bytes())).map(|module| {
+        assert!(module.is_instance_of::<WebAssembly::Module>());
+    })
+}
+
+#[wasm_bindgen_test(async)]
+fn instantiate_buffer() -> impl Future<Item = (), Error = JsValue> {
+    JsFuture::from(WebAssembly::instantiate_buffer(MODULE, &Object::new())).map(|result| {
+        let module = Reflect::get(&result, &"module".into());
+        assert!(module.is_instance_of::<WebAssembly::Module>());
+        assert_instance_answers(&Reflect::get(&result, &"instance".into()));
+    })
+}
+
+#[wasm_bindgen_test(async)]
+fn instantiate_module() -> impl Future<Item = (), Error = JsValue> {
+    let module = WebAssembly::Module::new(&module_bytes()).unwrap();
+    JsFuture::from(WebAssembly::instantiate_module(&module, &Object::new())).map(|instance| {
+        assert_instance_answers(&instance);
+    })
+}
+
+#[wasm_bindgen_test(async)]
+fn instantiate_streaming() -> Box<Future<Item = (), Error = JsValue>> {
+    // Neither `instantiateStreaming` nor `Response` exist in older node.
+    if !streaming_supported() {
+        return Box::new(future::ok(()))
+    }
+    let response = wasm_response(&module_bytes());
+    Box::new(JsFuture::from(WebAssembly::instantiate_streaming(&response, &Object::new())).map(|result| {
+        let module = Reflect::get(&result, &"module".into());
+        assert!(module.is_instance_of::<WebAssembly::Module>());
+        assert_instance_answers(&Reflect::get(&result, &"instance".into()));
+    }))
+}
+
+fn assert_instance_answers(instance: &JsValue) {
+    let instance: &WebAssembly::Instance = instance.dyn_ref().unwrap();
+    let ans: Function = Reflect::get(instance.exports().as_ref(), &"ans".into()).unchecked_into();
+    assert_eq!(ans.call0(&JsValue::undefined()).unwrap(), 42);
+}
+
+#[wasm_bindgen_test]
+fn module_new() {
+    let module = WebAssembly::Module::new(&module_bytes()).unwrap();
+    assert!(module.is_instance_of::<WebAssembly::Module>());
+
+    let module = WebAssembly::Module::new_with_bytes(MODULE).unwrap();
+    assert!(module.is_instance_of::<WebAssembly::Module>());
+
+    let error = WebAssembly::Module::new(&ArrayBuffer::new(42).into()).unwrap_err();
+    assert!(error.is_instance_of::<WebAssembly::CompileError>());
+}
+
+#[wasm_bindgen_test]
+fn module_introspection() {
+    let module = WebAssembly::Module::new(&module_bytes()).unwrap();
+
+    let exports = WebAssembly::Module::exports(&module);
+    assert_eq!(exports.length(), 1);
+    let export = Reflect::get(exports.as_ref(), &0.into());
+    assert_eq!(Reflect::get(&export, &"name".into()), "ans");
+    assert_eq!(Reflect::get(&export, &"kind".into()), "function");
+
+    assert_eq!(WebAssembly::Module::imports(&module).length(), 0);
+
+    let sections = WebAssembly::Module::custom_sections(&module, "foo");
+    assert_eq!(sections.length(), 1);
+    let section: ArrayBuffer = Reflect::get(sections.as_ref(), &0.into()).unchecked_into();
+    assert_eq!(section.byte_length(), 4);
+    assert_eq!(WebAssembly::Module::custom_sections(&module, "bar").length(), 0);
+}
+
+#[wasm_bindgen_test]
+fn instance() {
+    let module = WebAssembly::Module::new(&module_bytes()).unwrap();
+    let instance = WebAssembly::Instance::new(&module, &Object::new()).unwrap();
+    assert!(instance.is_instance_of::<WebAssembly::Instance>());
+
+    let ans: Function = Reflect::get(instance.exports().as_ref(), &"ans".into()).unchecked_into();
+    assert_eq!(ans.call0(&JsValue::undefined()).unwrap(), 42);
+}
+
+#[wasm_bindgen_test]
+fn memory() {
+    let memory = WebAssembly::Memory::new(&descriptor(&[
+        ("initial", 1.into()),
+        ("maximum", 2.into()),
+    ])).unwrap();
+    assert!(memory.is_instance_of::<WebAssembly::Memory>());
+
+    assert_eq!(memory.grow(1).unwrap(), 1);
+    let buffer: ArrayBuffer = memory.buffer().unchecked_into();
+    assert_eq!(buffer.byte_length(), 2 * 65536);
+
+    assert!(memory.grow(1).is_err());
+}
+
+#[wasm_bindgen_test]
+fn table() {
+    let table = WebAssembly::Table::new(&descriptor(&[
+        ("element", "anyfunc".into()),
+        ("initial", 1.into()),
+    ])).unwrap();
+    assert!(table.is_instance_of::<WebAssembly::Table>());
+
+    assert_eq!(table.length(), 1);
+    assert!(table.get(0).unwrap().is_null());
+    assert_eq!(table.grow(1).unwrap(), 1);
+    assert_eq!(table.length(), 2);
+    assert!(table.get(2).is_err());
+}
+
+#[wasm_bindgen_test]
+fn errors() {
+    let error = WebAssembly::CompileError::new("compile");
+    assert!(error.is_instance_of::<WebAssembly::CompileError>());
+    let base: &Error = error.as_ref();
+    assert_eq!(JsValue::from(base.message()), "compile");
+
+    let error = WebAssembly::LinkError::new("link");
+    assert!(error.is_instance_of::<WebAssembly::LinkError>());
+    let base: &Error = error.as_ref();
+    assert_eq!(JsValue::from(base.message()), "link");
+
+    let error = WebAssembly::RuntimeError::new("runtime");
+    assert!(error.is_instance_of::<WebAssembly::RuntimeError>());
+    let base: &Error = error.as_ref();
+    assert_eq!(JsValue::from(base.message()), "runtime");
+}
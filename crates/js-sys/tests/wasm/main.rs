@@ -2,8 +2,10 @@
 #![feature(use_extern_macros)]
 #![allow(non_snake_case)]
 
+extern crate futures;
 extern crate js_sys;
 extern crate wasm_bindgen;
+extern crate wasm_bindgen_futures;
 extern crate wasm_bindgen_test;
 
 pub mod global_fns;
@@ -52,6 +52,16 @@ impl BindgenAttrs {
             }).next()
     }
 
+    /// Get the first inline_js attribute
+    fn inline_js(&self) -> Option<&str> {
+        self.attrs
+            .iter()
+            .filter_map(|a| match a {
+                BindgenAttr::InlineJs(s) => Some(&s[..]),
+                _ => None,
+            }).next()
+    }
+
     /// Whether the catch attribute is present
     fn catch(&self) -> bool {
         self.attrs.iter().any(|a| match a {
@@ -209,6 +219,7 @@ pub enum BindgenAttr {
     StaticMethodOf(Ident),
     JsNamespace(Ident),
     Module(String),
+    InlineJs(String),
     Getter(Option<Ident>),
     Setter(Option<Ident>),
     IndexingGetter,
@@ -280,6 +291,13 @@ impl syn::synom::Synom for BindgenAttr {
             (s.value())
         )=> { BindgenAttr::Module }
         |
+        do_parse!(
+            call!(term, "inline_js") >>
+            punct!(=) >>
+            s: syn!(syn::LitStr) >>
+            (s.value())
+        )=> { BindgenAttr::InlineJs }
+        |
         do_parse!(
             call!(term, "js_name") >>
             punct!(=) >>
@@ -932,8 +950,25 @@ impl MacroParse<()> for syn::ItemEnum {
 }
 
 impl MacroParse<BindgenAttrs> for syn::ItemForeignMod {
-    fn macro_parse(self, program: &mut ast::Program, opts: BindgenAttrs) -> Result<(), Diagnostic> {
+    fn macro_parse(self, program: &mut ast::Program, mut opts: BindgenAttrs) -> Result<(), Diagnostic> {
         let mut errors = Vec::new();
+        if let Some(contents) = opts.inline_js().map(|s| s.to_string()) {
+            if opts.module().is_some() {
+                bail_span!(self, "cannot specify both `module` and `inline_js`");
+            }
+
+            // Inline JS is imported just like a module local to the crate,
+            // under a name derived from its contents so the same snippet is
+            // only emitted once.
+            let identifier = format!("inline{}.js", ShortHash(&contents));
+            if !program.inline_js.iter().any(|js| js.identifier == identifier) {
+                program.inline_js.push(ast::InlineJs {
+                    identifier: identifier.clone(),
+                    contents,
+                });
+            }
+            opts.attrs.push(BindgenAttr::Module(identifier));
+        }
         match self.abi.name {
             Some(ref l) if l.value() == "C" => {}
             None => {}
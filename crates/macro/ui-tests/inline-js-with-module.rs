@@ -0,0 +1,10 @@
+#![feature(use_extern_macros)]
+
+extern crate wasm_bindgen;
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "foo", inline_js = "export function bar() {}")]
+extern "C" {
+    fn bar();
+}
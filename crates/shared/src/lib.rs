@@ -3,7 +3,7 @@
 #[macro_use]
 extern crate serde_derive;
 
-pub const SCHEMA_VERSION: &str = "8";
+pub const SCHEMA_VERSION: &str = "9";
 
 #[derive(Deserialize)]
 pub struct ProgramOnlySchema {
@@ -17,10 +17,18 @@ pub struct Program {
     pub enums: Vec<Enum>,
     pub imports: Vec<Import>,
     pub structs: Vec<Struct>,
+    pub local_modules: Vec<LocalModule>,
+    pub crate_name: String,
     pub version: String,
     pub schema_version: String,
 }
 
+#[derive(Deserialize, Serialize)]
+pub struct LocalModule {
+    pub identifier: String,
+    pub contents: String,
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct Import {
     pub module: Option<String>,
@@ -131,6 +139,25 @@ pub struct StructField {
     pub comments: Vec<String>,
 }
 
+/// Returns whether `module`, as passed to `#[wasm_bindgen(module = "...")]`,
+/// refers to a JS file local to the crate rather than an external module.
+///
+/// Local modules are written as absolute paths starting at the root of the
+/// crate, for example `/js/helpers.js`.
+pub fn is_local_module(module: &str) -> bool {
+    module.starts_with('/')
+}
+
+/// The path, relative to the output directory, that the contents of the local
+/// module `module` of crate `crate_name` are written to.
+///
+/// This is used both for local JS files and for snippets of inline JS, the
+/// latter of which have generated identifiers without a leading `/`.
+pub fn local_module_path(crate_name: &str, module: &str) -> String {
+    let module = if is_local_module(module) { &module[1..] } else { module };
+    format!("snippets/{}/{}", crate_name, module)
+}
+
 pub fn new_function(struct_name: &str) -> String {
     let mut name = format!("__wbg_");
     name.extend(struct_name.chars().flat_map(|s| s.to_lowercase()));
@@ -18,9 +18,34 @@ pub fn wasm_bindgen_test(
 ) -> proc_macro::TokenStream {
     let mut attr = attr.into_iter();
     let mut async = false;
+    let mut timeout_ms = None;
+    let mut hook = None;
     while let Some(token) = attr.next() {
         match &token {
             proc_macro::TokenTree::Ident(i) if i.to_string() == "async" => async = true,
+            proc_macro::TokenTree::Ident(i)
+                if i.to_string() == "before_each" || i.to_string() == "after_each" =>
+            {
+                if hook.is_some() {
+                    panic!("a function can only be one of `before_each` or `after_each`");
+                }
+                hook = Some(i.to_string());
+            }
+            proc_macro::TokenTree::Ident(i) if i.to_string() == "timeout_ms" => {
+                match attr.next() {
+                    Some(proc_macro::TokenTree::Punct(ref op)) if op.as_char() == '=' => {}
+                    _ => panic!("malformed `#[wasm_bindgen_test]` attribute"),
+                }
+                match attr.next() {
+                    Some(proc_macro::TokenTree::Literal(lit)) => {
+                        match lit.to_string().parse::<u32>() {
+                            Ok(ms) => timeout_ms = Some(ms),
+                            Err(_) => panic!("`timeout_ms` must be a number of milliseconds"),
+                        }
+                    }
+                    _ => panic!("malformed `#[wasm_bindgen_test]` attribute"),
+                }
+            }
             _ => panic!("malformed `#[wasm_bindgen_test]` attribute"),
         }
         match &attr.next() {
@@ -30,11 +55,31 @@ pub fn wasm_bindgen_test(
         }
     }
 
-    let mut body = TokenStream::from(body).into_iter();
+    let mut body = TokenStream::from(body).into_iter().peekable();
 
-    // Skip over other attributes to `fn #ident ...`, and extract `#ident`
+    // Skip over other attributes to `fn #ident ...`, and extract `#ident`.
+    // Along the way pull out `#[should_panic]` and `#[ignore]` as those are
+    // implemented by the test harness rather than the compiler.
     let mut leading_tokens = Vec::new();
+    let mut should_panic = None;
+    let mut ignore = false;
     while let Some(token) = body.next() {
+        if let TokenTree::Punct(ref op) = token {
+            if op.as_char() == '#' {
+                let attr = match body.peek() {
+                    Some(TokenTree::Group(group)) => parse_test_attr(group),
+                    _ => None,
+                };
+                if let Some(attr) = attr {
+                    match attr {
+                        TestAttr::ShouldPanic(expected) => should_panic = Some(expected),
+                        TestAttr::Ignore => ignore = true,
+                    }
+                    body.next();
+                    continue
+                }
+            }
+        }
         leading_tokens.push(token.clone());
         if let TokenTree::Ident(token) = token {
             if token == "fn" {
@@ -47,17 +92,95 @@ pub fn wasm_bindgen_test(
         _ => panic!("expected a function name"),
     };
 
-    let mut tokens = Vec::<TokenTree>::new();
+    // Hooks aren't tests themselves, so none of the options for tests apply
+    // to them.
+    if let Some(ref hook) = hook {
+        if async {
+            panic!("`{}` hooks can't be `async`", hook);
+        }
+        if timeout_ms.is_some() {
+            panic!("`{}` hooks can't have a `timeout_ms`", hook);
+        }
+        if should_panic.is_some() {
+            panic!("`{}` hooks can't be `#[should_panic]`", hook);
+        }
+        if ignore {
+            panic!("`{}` hooks can't be `#[ignore]`", hook);
+        }
+    }
 
-    let test_body = if async {
-        quote! { cx.execute_async(test_name, #ident); }
+    let should_panic = match should_panic {
+        Some(Some(expected)) => quote! { Some(Some(#expected)) },
+        Some(None) => quote! { Some(None) },
+        None => quote! { None },
+    };
+    let timeout_ms = match timeout_ms {
+        Some(ms) => quote! { Some(#ms) },
+        None => quote! { None },
+    };
+    let meta = quote! {
+        ::wasm_bindgen_test::__rt::TestMeta {
+            should_panic: #should_panic,
+            ignore: #ignore,
+            timeout_ms: #timeout_ms,
+        }
+    };
+    let test_body = if let Some(hook) = hook {
+        // Hooks aren't tests themselves, they're registered to run around
+        // every test instead.
+        let hook = Ident::new(&hook, Span::call_site());
+        quote! { let _ = test_name; cx.#hook(#ident); }
+    } else if async {
+        quote! { cx.execute_async(test_name, #ident, #meta); }
     } else {
-        quote! { cx.execute_sync(test_name, #ident); }
+        quote! { cx.execute_sync(test_name, #ident, #meta); }
+    };
+
+    expand(leading_tokens, ident, body, test_body)
+}
+
+#[proc_macro_attribute]
+pub fn wasm_bindgen_bench(
+    attr: proc_macro::TokenStream,
+    body: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    if !attr.is_empty() {
+        panic!("`#[wasm_bindgen_bench]` doesn't take any arguments");
+    }
+
+    // Skip over other attributes to `fn #ident ...`, and extract `#ident`.
+    let mut body = TokenStream::from(body).into_iter();
+    let mut leading_tokens = Vec::new();
+    while let Some(token) = body.next() {
+        leading_tokens.push(token.clone());
+        if let TokenTree::Ident(token) = token {
+            if token == "fn" {
+                break
+            }
+        }
+    }
+    let ident = match body.next() {
+        Some(TokenTree::Ident(token)) => token,
+        _ => panic!("expected a function name"),
     };
 
+    let test_body = quote! { cx.execute_bench(test_name, #ident); };
+    expand(leading_tokens, ident, body, test_body)
+}
+
+/// Generates the test function itself, along with its entry point for the
+/// test harness which runs `test_body`.
+fn expand(
+    leading_tokens: Vec<TokenTree>,
+    ident: Ident,
+    body: impl Iterator<Item = TokenTree>,
+    test_body: TokenStream,
+) -> proc_macro::TokenStream {
+    let mut tokens = Vec::<TokenTree>::new();
+
     // We generate a `#[no_mangle]` with a known prefix so the test harness can
     // later slurp up all of these functions and pass them as arguments to the
-    // main test harness. This is the entry point for all tests.
+    // main test harness. This is the entry point for all tests and benchmarks.
     let name = format!("__wbg_test_{}_{}", ident, CNT.fetch_add(1, Ordering::SeqCst));
     let name = Ident::new(&name, Span::call_site());
     tokens.extend((quote! {
@@ -77,3 +200,62 @@ pub fn wasm_bindgen_test(
 
     tokens.into_iter().collect::<TokenStream>().into()
 }
+
+enum TestAttr {
+    ShouldPanic(Option<Literal>),
+    Ignore,
+}
+
+/// Parses the bracketed contents of an attribute on a test function, returning
+/// `None` if it's not one of the attributes we handle ourselves.
+fn parse_test_attr(group: &Group) -> Option<TestAttr> {
+    if group.delimiter() != Delimiter::Bracket {
+        return None
+    }
+    let mut tokens = group.stream().into_iter();
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(i)) => i.to_string(),
+        _ => return None,
+    };
+    match &name[..] {
+        "should_panic" => {}
+        // `#[ignore = "reason"]` is accepted, but the reason isn't used.
+        "ignore" => return Some(TestAttr::Ignore),
+        _ => return None,
+    }
+
+    // Parse `#[should_panic(expected = "...")]`, or the shorthand
+    // `#[should_panic = "..."]`
+    let args = match tokens.next() {
+        Some(TokenTree::Group(ref g)) if g.delimiter() == Delimiter::Parenthesis => g.stream(),
+        Some(TokenTree::Punct(ref op)) if op.as_char() == '=' => {
+            let expected = match tokens.next() {
+                Some(TokenTree::Literal(lit)) => lit,
+                _ => panic!("malformed `#[should_panic]` attribute"),
+            };
+            if tokens.next().is_some() {
+                panic!("malformed `#[should_panic]` attribute");
+            }
+            return Some(TestAttr::ShouldPanic(Some(expected)))
+        }
+        Some(_) => panic!("malformed `#[should_panic]` attribute"),
+        None => return Some(TestAttr::ShouldPanic(None)),
+    };
+    let mut args = args.into_iter();
+    match args.next() {
+        Some(TokenTree::Ident(ref i)) if i.to_string() == "expected" => {}
+        _ => panic!("malformed `#[should_panic]` attribute"),
+    }
+    match args.next() {
+        Some(TokenTree::Punct(ref op)) if op.as_char() == '=' => {}
+        _ => panic!("malformed `#[should_panic]` attribute"),
+    }
+    let expected = match args.next() {
+        Some(TokenTree::Literal(lit)) => lit,
+        _ => panic!("malformed `#[should_panic]` attribute"),
+    };
+    if args.next().is_some() {
+        panic!("malformed `#[should_panic]` attribute");
+    }
+    Some(TestAttr::ShouldPanic(Some(expected)))
+}
@@ -8,3 +8,33 @@ extern crate wasm_bindgen_test;
 wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
 
 pub mod common;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen]
+extern {
+    type Element;
+    #[wasm_bindgen(method, getter, structural, js_name = childElementCount)]
+    fn child_element_count(this: &Element) -> u32;
+    #[wasm_bindgen(method, setter, structural, js_name = innerHTML)]
+    fn set_inner_html(this: &Element, html: &str);
+}
+
+#[wasm_bindgen_test(before_each)]
+fn setup() {
+    console_log!("before each test");
+}
+
+#[wasm_bindgen_test(after_each)]
+fn teardown() {
+    console_log!("after each test");
+}
+
+#[wasm_bindgen_test]
+fn pass_fixture() {
+    let container: Element = fixture::container();
+    assert_eq!(container.child_element_count(), 0);
+    container.set_inner_html("<p>hello</p>");
+    assert_eq!(container.child_element_count(), 1);
+}
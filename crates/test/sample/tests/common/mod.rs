@@ -43,3 +43,56 @@ fn fail_after_3s() -> impl Future<Item = (), Error = JsValue> {
         })
 }
 
+#[wasm_bindgen_test(async, timeout_ms = 1000)]
+fn fail_timeout() -> impl Future<Item = (), Error = JsValue> {
+    console_log!("log before timing out");
+    Timeout::new(Duration::new(2, 0))
+}
+
+#[wasm_bindgen_test]
+#[should_panic]
+fn should_panic() {
+    panic!("this test should pass because it panics");
+}
+
+#[wasm_bindgen_test]
+#[should_panic(expected = "expected message")]
+fn should_panic_with_message() {
+    panic!("this is the expected message");
+}
+
+#[wasm_bindgen_test]
+#[should_panic = "expected message"]
+fn should_panic_with_message_shorthand() {
+    panic!("this is the expected message");
+}
+
+#[wasm_bindgen_test]
+#[should_panic(expected = "expected message")]
+fn should_panic_with_wrong_message() {
+    panic!("this is not the right message");
+}
+
+#[wasm_bindgen_test]
+#[should_panic]
+fn should_panic_but_does_not() {
+    console_log!("this test fails because it doesn't panic");
+}
+
+#[wasm_bindgen_test(async)]
+#[should_panic]
+fn should_panic_but_only_errors() -> impl Future<Item = (), Error = JsValue> {
+    console_log!("this test fails because returning an error isn't a panic");
+    Err::<(), JsValue>(JsValue::from("not a panic")).into_future()
+}
+
+#[wasm_bindgen_test]
+#[ignore]
+fn ignored() {
+    panic!("this test is ignored and never runs");
+}
+
+#[wasm_bindgen_bench]
+fn bench_format(b: &mut Bencher) {
+    b.iter(|| format!("{} {}", "hello", 42));
+}
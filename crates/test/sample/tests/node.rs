@@ -6,3 +6,31 @@ extern crate wasm_bindgen;
 extern crate wasm_bindgen_test;
 
 pub mod common;
+
+use std::cell::Cell;
+
+use wasm_bindgen_test::*;
+
+thread_local!(static AWAITING_AFTER_EACH: Cell<bool> = Cell::new(false));
+
+// Fails whichever test runs next if the after-each hook was skipped for
+// `should_panic_runs_after_each`.
+#[wasm_bindgen_test(before_each)]
+fn check_after_each_ran() {
+    assert!(
+        !AWAITING_AFTER_EACH.with(|a| a.get()),
+        "after-each hook didn't run after a panicking test"
+    );
+}
+
+#[wasm_bindgen_test(after_each)]
+fn record_after_each() {
+    AWAITING_AFTER_EACH.with(|a| a.set(false));
+}
+
+#[wasm_bindgen_test]
+#[should_panic]
+fn should_panic_runs_after_each() {
+    AWAITING_AFTER_EACH.with(|a| a.set(true));
+    panic!("after-each hooks still run after this");
+}
@@ -0,0 +1,10 @@
+#![feature(use_extern_macros)]
+
+extern crate futures;
+extern crate sample;
+extern crate wasm_bindgen;
+extern crate wasm_bindgen_test;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_shared_worker);
+
+pub mod common;
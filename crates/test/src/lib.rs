@@ -14,7 +14,9 @@ extern crate wasm_bindgen;
 extern crate wasm_bindgen_futures;
 extern crate wasm_bindgen_test_macro;
 
-pub use wasm_bindgen_test_macro::wasm_bindgen_test;
+pub use wasm_bindgen_test_macro::{wasm_bindgen_test, wasm_bindgen_bench};
+pub use __rt::bench::Bencher;
+pub use __rt::fixture;
 
 /// Helper macro which acts like `println!` only routes to `console.log`
 /// instead.
@@ -37,6 +39,10 @@ macro_rules! console_log {
 ///
 /// * `run_in_browser` - requires that this test is run in a browser rather than
 ///   node.js, which is the default for executing tests.
+/// * `run_in_worker` - requires that this test is run in a dedicated web worker
+///   in a browser, for code using worker-only APIs like `importScripts`.
+/// * `run_in_shared_worker` - like `run_in_worker`, but runs tests in a
+///   `SharedWorker`.
 ///
 /// This macro may be invoked at most one time per test suite (an entire binary
 /// like `tests/foo.rs`, not per module)
@@ -48,6 +54,18 @@ macro_rules! wasm_bindgen_test_configure {
         pub static __WBG_TEST_RUN_IN_BROWSER: [u8; 1] = [0x01];
         $crate::wasm_bindgen_test_configure!($($others)*);
     );
+    (run_in_worker $($others:tt)*) => (
+        #[link_section = "__wasm_bindgen_test_unstable"]
+        #[cfg(target_arch = "wasm32")]
+        pub static __WBG_TEST_RUN_IN_WORKER: [u8; 1] = [0x02];
+        $crate::wasm_bindgen_test_configure!($($others)*);
+    );
+    (run_in_shared_worker $($others:tt)*) => (
+        #[link_section = "__wasm_bindgen_test_unstable"]
+        #[cfg(target_arch = "wasm32")]
+        pub static __WBG_TEST_RUN_IN_SHARED_WORKER: [u8; 1] = [0x03];
+        $crate::wasm_bindgen_test_configure!($($others)*);
+    );
     () => ()
 }
 
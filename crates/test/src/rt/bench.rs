@@ -0,0 +1,160 @@
+//! Support for `#[wasm_bindgen_bench]` benchmarks, modeled after libtest's
+//! `Bencher`.
+
+use std::mem;
+use std::ptr;
+
+use wasm_bindgen::prelude::*;
+
+// Number of samples taken of each benchmark.
+const SAMPLES: usize = 50;
+
+// Minimum number of milliseconds each sample should take. Timers in browsers
+// are often coarsened, so each sample runs a benchmark enough times to make
+// timer resolution insignificant.
+const SAMPLE_MS: f64 = 5.0;
+
+// Maximum number of iterations in one sample. If a sample still doesn't take
+// `SAMPLE_MS` by then the timer is most likely not advancing at all, which
+// clamped timers can look like for very fast benchmarks.
+const MAX_SAMPLE_ITERATIONS: u64 = 1 << 30;
+
+#[wasm_bindgen]
+extern {
+    #[wasm_bindgen(js_namespace = performance)]
+    fn now() -> f64;
+}
+
+/// Manager of the benchmarking runs, passed to functions annotated with
+/// `#[wasm_bindgen_bench]`.
+///
+/// The benchmark function calls `iter` with the code to be measured.
+pub struct Bencher {
+    measure: bool,
+    summary: Option<Summary>,
+}
+
+/// Statistics of the nanoseconds per iteration of a benchmark.
+#[derive(Clone, Copy)]
+pub(crate) struct Summary {
+    /// Median nanoseconds per iteration.
+    pub(crate) median: f64,
+    /// Difference between the slowest and fastest samples, in nanoseconds per
+    /// iteration, after discarding outliers.
+    pub(crate) deviation: f64,
+}
+
+impl Bencher {
+    /// Creates a new `Bencher`, which only runs the benchmark once unless
+    /// `measure` is set, as is done when benchmarks are run as tests.
+    pub(crate) fn new(measure: bool) -> Bencher {
+        Bencher {
+            measure,
+            summary: None,
+        }
+    }
+
+    /// Measures the execution time of `inner`, running it many times.
+    ///
+    /// The return value of `inner` is passed through a "black box" so the
+    /// compiler can't optimize away its computation.
+    pub fn iter<T, F>(&mut self, mut inner: F)
+        where F: FnMut() -> T,
+    {
+        if !self.measure {
+            black_box(inner());
+            return
+        }
+
+        // Figure out how many iterations make up one sample, doubling the
+        // count until a sample is long enough to be measured reliably.
+        let mut n = 1u64;
+        while time(&mut inner, n) < SAMPLE_MS {
+            n *= 2;
+            if n > MAX_SAMPLE_ITERATIONS {
+                panic!(
+                    "benchmark took less than {}ms for {} iterations, is \
+                     `performance.now()` advancing?",
+                    SAMPLE_MS,
+                    MAX_SAMPLE_ITERATIONS
+                );
+            }
+        }
+
+        let mut samples = (0..SAMPLES)
+            .map(|_| time(&mut inner, n) * 1_000_000.0 / n as f64)
+            .collect::<Vec<_>>();
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        winsorize(&mut samples, 5.0);
+        self.summary = Some(Summary {
+            median: percentile(&samples, 50.0),
+            deviation: samples[samples.len() - 1] - samples[0],
+        });
+    }
+
+    /// Returns the statistics gathered by `iter`, if it was called while
+    /// measuring.
+    pub(crate) fn summary(&self) -> Option<Summary> {
+        self.summary
+    }
+}
+
+/// Returns how many milliseconds running `inner` `n` times took.
+fn time<T, F: FnMut() -> T>(inner: &mut F, n: u64) -> f64 {
+    let start = now();
+    for _ in 0..n {
+        black_box(inner());
+    }
+    now() - start
+}
+
+/// A function that is opaque to the optimizer, to allow benchmarks to pretend
+/// to use outputs to assist in avoiding dead-code elimination.
+fn black_box<T>(dummy: T) -> T {
+    unsafe {
+        let ret = ptr::read_volatile(&dummy);
+        mem::forget(dummy);
+        ret
+    }
+}
+
+/// Returns the `pct` percentile of the sorted `samples`, interpolating
+/// between samples.
+fn percentile(samples: &[f64], pct: f64) -> f64 {
+    if samples.len() == 1 {
+        return samples[0]
+    }
+    let rank = (pct / 100.0) * (samples.len() - 1) as f64;
+    let lrank = rank.floor();
+    let n = lrank as usize;
+    let lo = samples[n];
+    let hi = samples[(n + 1).min(samples.len() - 1)];
+    lo + (hi - lo) * (rank - lrank)
+}
+
+/// Clamps the sorted `samples` to the `pct` and `100 - pct` percentiles, like
+/// libtest does, to discard outliers.
+fn winsorize(samples: &mut [f64], pct: f64) {
+    let lo = percentile(samples, pct);
+    let hi = percentile(samples, 100.0 - pct);
+    for sample in samples.iter_mut() {
+        if *sample > hi {
+            *sample = hi
+        } else if *sample < lo {
+            *sample = lo
+        }
+    }
+}
+
+/// Formats `n` with thousands separators like libtest, e.g. `1,234,567`.
+pub(crate) fn fmt_thousands(n: f64) -> String {
+    let digits = (n.max(0.0).round() as u64).to_string();
+    let mut dst = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            dst.push(',');
+        }
+        dst.push(c);
+    }
+    dst
+}
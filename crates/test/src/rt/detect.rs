@@ -1,4 +1,4 @@
-//! Runtime detection of whether we're in node.js or a browser.
+//! Runtime detection of whether we're in node.js, a browser, or a web worker.
 
 use wasm_bindgen::prelude::*;
 use js_sys::Function;
@@ -8,6 +8,8 @@ extern {
     type This;
     #[wasm_bindgen(method, getter, structural, js_name = self)]
     fn self_(me: &This) -> JsValue;
+    #[wasm_bindgen(method, getter, structural)]
+    fn document(me: &This) -> JsValue;
 }
 
 /// Returns whether it's likely we're executing in a browser environment, as
@@ -53,9 +55,21 @@ pub fn is_browser() -> bool {
     // * Last but not least, test whether `self` is defined or not.
     //
     // Whew!
+    global().self_() != JsValue::undefined()
+}
+
+/// Returns whether we're executing in a web worker rather than on a page,
+/// assuming that `is_browser` returned `true`.
+pub fn is_worker() -> bool {
+    // Workers have a `self` but no `document`, see `is_browser` for how we
+    // get at these values.
+    global().document() == JsValue::undefined()
+}
+
+fn global() -> This {
     let this = Function::new_no_args("return this")
         .call0(&JsValue::undefined())
         .unwrap();
     assert!(this != JsValue::undefined());
-    This::from(this).self_() != JsValue::undefined()
+    This::from(this)
 }
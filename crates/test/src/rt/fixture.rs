@@ -0,0 +1,77 @@
+//! Per-test DOM fixtures for browser tests.
+//!
+//! All browser tests share one page, so tests which modify the DOM can easily
+//! step on each other's toes. The functions here give each test its own
+//! elements to work with, which are removed from the page once the test has
+//! finished, whether it passed or not.
+//!
+//! ```ignore
+//! #[wasm_bindgen_test]
+//! fn renders() {
+//!     let container: web_sys::HtmlElement = fixture::container();
+//!     render_into(&container);
+//!     assert_eq!(container.child_element_count(), 1);
+//! }
+//! ```
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[wasm_bindgen]
+extern {
+    type HTMLDocument;
+    static document: HTMLDocument;
+    #[wasm_bindgen(method, structural, js_name = createElement)]
+    fn create_element(this: &HTMLDocument, tag: &str) -> Element;
+    #[wasm_bindgen(method, getter, structural)]
+    fn body(this: &HTMLDocument) -> Element;
+
+    type Element;
+    #[wasm_bindgen(method, structural, js_name = appendChild)]
+    fn append_child(this: &Element, child: &Element);
+    #[wasm_bindgen(method, structural)]
+    fn remove(this: &Element);
+    #[wasm_bindgen(method, getter, structural, js_name = contentDocument)]
+    fn content_document(this: &Element) -> JsValue;
+}
+
+/// Returns a new, empty `<div>` appended to the page's `<body>`, for the
+/// currently running test to render into.
+///
+/// The element is removed from the page once the test finishes. This must be
+/// called while a test is running in a browser, and panics otherwise.
+pub fn container<T: JsCast>() -> T {
+    let div = create("div");
+    JsValue::from(div).unchecked_into()
+}
+
+/// Returns the `Document` of a new `<iframe>` appended to the page's
+/// `<body>`, for tests which need a whole document to themselves.
+///
+/// The iframe is removed from the page once the test finishes. This must be
+/// called while a test is running in a browser, and panics otherwise.
+pub fn iframe_document<T: JsCast>() -> T {
+    let iframe = create("iframe");
+    iframe.content_document().unchecked_into()
+}
+
+fn create(tag: &str) -> Element {
+    if !super::CURRENT_FIXTURES.is_set() {
+        panic!("DOM fixtures can only be created while a test is running");
+    }
+    if !super::detect::is_browser() || super::detect::is_worker() {
+        panic!("DOM fixtures can only be created in browser tests");
+    }
+    let element = document.create_element(tag);
+    document.body().append_child(&element);
+    let element = JsValue::from(element);
+    super::CURRENT_FIXTURES.with(|f| f.borrow_mut().push(element.clone()));
+    element.unchecked_into()
+}
+
+/// Removes all `fixtures` created by a test from the page.
+pub(crate) fn remove_all(fixtures: &mut Vec<JsValue>) {
+    for fixture in fixtures.drain(..) {
+        fixture.unchecked_ref::<Element>().remove();
+    }
+}
@@ -58,7 +58,7 @@
 //
 // * A `Context` is created. The `Context` is forwarded the CLI arguments of the
 //   original `wasm-bindgen-test-runner` in an environment specific fashion.
-//   This is used for test filters today.
+//   This is used for test filters and libtest-like flags such as `--exact`.
 //
 // * The `Context::run` function is called. Again, the generated JS has gathered
 //   all wasm tests to be executed into a list, and it's passed in here.
@@ -90,26 +90,37 @@
 
 use std::cell::{RefCell, Cell};
 use std::fmt;
+use std::panic;
 use std::rc::Rc;
+use std::sync::{Once, ONCE_INIT};
 
 use console_error_panic_hook;
 use futures::future;
 use futures::prelude::*;
-use js_sys::{Array, Function, Promise};
+use futures::task;
+use js_sys::{Array, Date, Error, Function, Promise};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::future_to_promise;
 
-// Maximum number of tests to execute concurrently. Eventually this should be a
-// configuration option specified at runtime or at compile time rather than
-// baked in here.
+// Default maximum number of tests to execute concurrently, which can be
+// configured at runtime with `--test-threads`.
 //
 // Currently the default is 1 because the DOM has a lot of shared state, and
 // conccurrently doing things by default would likely end up in a bad situation.
 const CONCURRENCY: usize = 1;
 
+// Default number of milliseconds an asynchronous test may take before it's
+// considered hung and reported as failed, which can be configured at runtime
+// with `--test-timeout` and per test with `timeout_ms`.
+const TIMEOUT_MS: u32 = 10_000;
+
+pub mod bench;
 pub mod node;
 pub mod browser;
 pub mod detect;
+pub mod fixture;
+pub mod worker;
+mod report;
 
 /// Runtime test harness support instantiated in JS.
 ///
@@ -122,16 +133,67 @@ pub struct Context {
 
 struct State {
     /// An optional filter used to restrict which tests are actually executed
-    /// and which are ignored. This is passed via the `args` function which
-    /// comes from the command line of `wasm-bindgen-test-runner`. Currently
-    /// this is the only "CLI option"
+    /// and which are filtered out. This is passed via the `args` function
+    /// which comes from the command line of `wasm-bindgen-test-runner`.
     filter: RefCell<Option<String>>,
 
+    /// Whether `filter` and `skip` must match test names exactly (`--exact`)
+    /// rather than as substrings.
+    exact: Cell<bool>,
+
+    /// Tests matching any of these are filtered out (`--skip`).
+    skip: RefCell<Vec<String>>,
+
+    /// Only list the tests rather than running them (`--list`).
+    list: Cell<bool>,
+
+    /// Only run tests annotated with `#[ignore]` (`--ignored`).
+    only_ignored: Cell<bool>,
+
+    /// Whether benchmarks are measured, with `--bench`. Otherwise they're
+    /// executed once like tests, and regular tests are ignored when
+    /// benchmarking.
+    bench: Cell<bool>,
+
+    /// Don't capture output of tests, printing it directly instead
+    /// (`--nocapture`).
+    nocapture: Cell<bool>,
+
+    /// Maximum number of tests to execute concurrently (`--test-threads`).
+    concurrency: Cell<usize>,
+
     /// Counter of the number of tests that have succeeded.
     succeeded: Cell<usize>,
 
-    /// Counter of the number of tests that have been ignored
-    ignored: Cell<usize>,
+    /// Names of the tests that have been ignored.
+    ignored: RefCell<Vec<String>>,
+
+    /// Counter of the number of tests that have been filtered out.
+    filtered_out: Cell<usize>,
+
+    /// Counter of the number of benchmarks which have been measured.
+    measured: Cell<usize>,
+
+    /// Counters of the number of tests and benchmarks printed with `--list`.
+    listed_tests: Cell<usize>,
+    listed_benches: Cell<usize>,
+
+    /// Functions registered with `#[wasm_bindgen_test(before_each)]` and
+    /// `#[wasm_bindgen_test(after_each)]`.
+    hooks: Rc<Hooks>,
+
+    /// Default timeout of asynchronous tests in milliseconds, where 0 means
+    /// tests never time out.
+    timeout_ms: Cell<u32>,
+
+    /// Results of all tests which have been run or ignored, used to generate
+    /// machine-readable reports with `Context::report`.
+    reports: RefCell<Vec<report::TestReport>>,
+
+    /// When the test run started and how long it took in total, in
+    /// milliseconds.
+    started: Cell<f64>,
+    duration: Cell<f64>,
 
     /// A list of all tests which have failed.
     ///
@@ -152,6 +214,21 @@ struct State {
     formatter: Box<Formatter>,
 }
 
+/// Metadata about a test gathered by the `#[wasm_bindgen_test]` macro from
+/// the test function's attributes.
+pub struct TestMeta {
+    /// `Some` if the test is annotated with `#[should_panic]`, containing the
+    /// `expected` substring of the panic message, if any.
+    pub should_panic: Option<Option<&'static str>>,
+
+    /// Whether the test is annotated with `#[ignore]`.
+    pub ignore: bool,
+
+    /// The `timeout_ms` of `#[wasm_bindgen_test(timeout_ms = ...)]`, if
+    /// specified, overriding the harness's default timeout.
+    pub timeout_ms: Option<u32>,
+}
+
 /// Representation of one test that needs to be executed.
 ///
 /// Tests are all represented as futures, and tests perform no work until their
@@ -160,6 +237,19 @@ struct Test {
     name: String,
     future: Box<Future<Item = (), Error = JsValue>>,
     output: Rc<RefCell<Output>>,
+    should_panic: Option<Option<&'static str>>,
+    /// When this test first started executing, in milliseconds.
+    started: f64,
+    /// How long this test may run for in milliseconds, or 0 for no limit.
+    timeout_ms: u32,
+    /// Timer which wakes up the harness once this test has timed out, set
+    /// once the test is running asynchronously.
+    timeout: Option<Timeout>,
+    /// For benchmarks, where their measurements are stored once finished.
+    bench: Option<Rc<Cell<Option<bench::Summary>>>>,
+    /// DOM elements created for this test through the `fixture` module,
+    /// removed once the test finishes.
+    fixtures: Rc<RefCell<Vec<JsValue>>>,
 }
 
 /// Captured output of each test.
@@ -167,6 +257,17 @@ struct Test {
 struct Output {
     log: String,
     error: String,
+    /// Whether output is passed through rather than captured, see
+    /// `--nocapture`.
+    nocapture: bool,
+    /// The message of the panic that happened during this test, if any.
+    panic: Option<String>,
+    /// Whether a before-each or after-each hook is currently running.
+    in_hook: bool,
+    /// The message of the panic that happened in one of the hooks, if any,
+    /// which is kept apart from `panic` as it doesn't count for
+    /// `#[should_panic]`.
+    hook_panic: Option<String>,
 }
 
 trait Formatter {
@@ -206,17 +307,35 @@ impl Context {
     /// tests.
     #[wasm_bindgen(constructor)]
     pub fn new() -> Context {
-        console_error_panic_hook::set_once();
+        static SET_HOOK: Once = ONCE_INIT;
+        SET_HOOK.call_once(|| panic::set_hook(Box::new(panic_hook)));
 
         let formatter = match node::Node::new() {
             Some(node) => Box::new(node) as Box<Formatter>,
+            None if detect::is_worker() => Box::new(worker::Worker::new()),
             None => Box::new(browser::Browser::new()),
         };
         Context {
             state: Rc::new(State {
                 filter: Default::default(),
+                exact: Default::default(),
+                skip: Default::default(),
+                list: Default::default(),
+                only_ignored: Default::default(),
+                bench: Default::default(),
+                nocapture: Default::default(),
+                concurrency: Cell::new(CONCURRENCY),
+                timeout_ms: Cell::new(TIMEOUT_MS),
                 failures: Default::default(),
                 ignored: Default::default(),
+                filtered_out: Default::default(),
+                measured: Default::default(),
+                listed_tests: Default::default(),
+                listed_benches: Default::default(),
+                hooks: Default::default(),
+                reports: Default::default(),
+                started: Default::default(),
+                duration: Default::default(),
                 remaining: Default::default(),
                 running: Default::default(),
                 succeeded: Default::default(),
@@ -228,23 +347,47 @@ impl Context {
     /// Inform this context about runtime arguments passed to the test
     /// harness.
     ///
-    /// Eventually this will be used to support flags, but for now it's just
-    /// used to support test filters.
+    /// This supports a subset of the flags of libtest, the harness used for
+    /// native Rust tests, along with at most one test filter.
     pub fn args(&mut self, args: Vec<JsValue>) {
-        // Here we want to reject all flags like `--foo` or `-f` as we don't
-        // support anything, and also we only support at most one non-flag
-        // argument as a test filter.
-        //
-        // Everything else is rejected.
         let mut filter = self.state.filter.borrow_mut();
-        for arg in args {
-            let arg = arg.as_string().unwrap();
-            if arg.starts_with("-") {
-                panic!("flag {} not supported", arg);
-            } else if filter.is_some() {
-                panic!("more than one filter argument cannot be passed");
+        let mut args = args.into_iter().map(|arg| arg.as_string().unwrap());
+        while let Some(arg) = args.next() {
+            // Flags taking a value accept both `--flag value` and
+            // `--flag=value`.
+            let (flag, inline_value) = match arg.find('=') {
+                Some(i) if arg.starts_with("--") => (&arg[..i], Some(arg[i + 1..].to_string())),
+                _ => (&arg[..], None),
+            };
+            let mut value = || {
+                inline_value.clone().or_else(|| args.next()).unwrap_or_else(|| {
+                    panic!("flag {} requires a value", flag)
+                })
+            };
+            match flag {
+                "--exact" => self.state.exact.set(true),
+                "--skip" => self.state.skip.borrow_mut().push(value()),
+                "--list" => self.state.list.set(true),
+                "--ignored" => self.state.only_ignored.set(true),
+                "--bench" => self.state.bench.set(true),
+                "--nocapture" => self.state.nocapture.set(true),
+                "--test-threads" => {
+                    let n = value().parse::<usize>().ok().filter(|n| *n > 0);
+                    match n {
+                        Some(n) => self.state.concurrency.set(n),
+                        None => panic!("--test-threads must be a positive integer"),
+                    }
+                }
+                "--test-timeout" => match value().parse::<u32>() {
+                    Ok(ms) => self.state.timeout_ms.set(ms),
+                    Err(_) => panic!("--test-timeout must be a number of milliseconds"),
+                },
+                s if s.starts_with("-") => panic!("flag {} not supported", s),
+                _ if filter.is_some() => {
+                    panic!("more than one filter argument cannot be passed")
+                }
+                _ => *filter = Some(arg.clone()),
             }
-            *filter = Some(arg);
         }
     }
 
@@ -258,9 +401,7 @@ impl Context {
     /// The promise returned resolves to either `true` if all tests passed or
     /// `false` if at least one test failed.
     pub fn run(&self, tests: Vec<JsValue>) -> Promise {
-        let noun = if tests.len() == 1 { "test" } else { "tests" };
-        self.state.formatter.writeln(&format!("running {} {}", tests.len(), noun));
-        self.state.formatter.writeln("");
+        self.state.started.set(Date::now());
 
         // Execute all our test functions through their wasm shims (unclear how
         // to pass native function pointers around here). Each test will
@@ -277,6 +418,36 @@ impl Context {
             }
         }
 
+        // When only listing tests we've already printed each test, so finish
+        // up without running anything.
+        if self.state.list.get() {
+            let benches = self.state.listed_benches.get();
+            let listed = self.state.listed_tests.get();
+            let noun = if listed == 1 { "test" } else { "tests" };
+            self.state.formatter.writeln("");
+            self.state.formatter.writeln(&format!("{} {}, {} benchmarks", listed, noun, benches));
+            return Promise::resolve(&JsValue::from(true));
+        }
+
+        {
+            let ignored = self.state.ignored.borrow();
+            let count = self.state.remaining.borrow().len() + ignored.len();
+            let noun = if count == 1 { "test" } else { "tests" };
+            self.state.formatter.writeln(&format!("running {} {}", count, noun));
+            self.state.formatter.writeln("");
+            for name in ignored.iter() {
+                self.state.formatter.writeln(&format!("test {} ... ignored", name));
+                self.state.reports.borrow_mut().push(report::TestReport {
+                    name: name.clone(),
+                    status: report::Status::Ignored,
+                    duration_ms: 0.0,
+                    log: String::new(),
+                    error: String::new(),
+                    failure: None,
+                });
+            }
+        }
+
         // Now that we've collected all our tests we wrap everything up in a
         // future to actually do all the processing, and pass it out to JS as a
         // `Promise`.
@@ -284,9 +455,44 @@ impl Context {
             .map_err(|e| match e {});
         future_to_promise(future)
     }
+
+    /// Renders a machine-readable report of the test run after `run` has
+    /// finished.
+    ///
+    /// The `format` is either `json`, for libtest-style JSON events, or
+    /// `junit`, for JUnit XML.
+    pub fn report(&self, format: &str) -> String {
+        let reports = self.state.reports.borrow();
+        let summary = report::Summary {
+            tests: &reports,
+            filtered_out: self.state.filtered_out.get(),
+            duration_ms: self.state.duration.get(),
+        };
+        match format {
+            "json" => summary.json(),
+            "junit" => summary.junit(),
+            _ => panic!("unknown report format `{}`", format),
+        }
+    }
 }
 
 scoped_thread_local!(static CURRENT_OUTPUT: RefCell<Output>);
+scoped_thread_local!(static CURRENT_FIXTURES: RefCell<Vec<JsValue>>);
+
+/// Hooks which run around every test.
+#[derive(Default)]
+struct Hooks {
+    before_each: RefCell<Vec<fn()>>,
+    after_each: RefCell<Vec<fn()>>,
+}
+
+/// Runs `hooks` for the current test, recording any panic in them apart from
+/// the test's own.
+fn run_hooks(hooks: &[fn()]) {
+    CURRENT_OUTPUT.with(|output| output.borrow_mut().in_hook = true);
+    hooks.iter().for_each(|hook| hook());
+    CURRENT_OUTPUT.with(|output| output.borrow_mut().in_hook = false);
+}
 
 /// Handler for `console.log` invocations.
 ///
@@ -313,8 +519,33 @@ pub fn __wbgtest_console_error(original: &Function, args: &Array) {
     record(original, args, |output| &mut output.error)
 }
 
+/// Panic hook which records the panic message for the currently running test,
+/// to later match it against `#[should_panic(expected = "...")]`, before
+/// printing it like `console_error_panic_hook` does.
+fn panic_hook(info: &panic::PanicInfo) {
+    if CURRENT_OUTPUT.is_set() {
+        let payload = info.payload();
+        let msg = match payload.downcast_ref::<&'static str>() {
+            Some(s) => s.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some(s) => s.clone(),
+                None => String::from("Box<Any>"),
+            },
+        };
+        CURRENT_OUTPUT.with(|output| {
+            let mut output = output.borrow_mut();
+            if output.in_hook {
+                output.hook_panic = Some(msg);
+            } else {
+                output.panic = Some(msg);
+            }
+        });
+    }
+    console_error_panic_hook::hook(info);
+}
+
 fn record(orig: &Function, args: &Array, dst: impl FnOnce(&mut Output) -> &mut String) {
-    if !CURRENT_OUTPUT.is_set() {
+    if !CURRENT_OUTPUT.is_set() || CURRENT_OUTPUT.with(|output| output.borrow().nocapture) {
         drop(orig.apply(&JsValue::null(), args));
         return
     }
@@ -335,50 +566,176 @@ fn record(orig: &Function, args: &Array, dst: impl FnOnce(&mut Output) -> &mut S
 impl Context {
     /// Entry point for a synchronous test in wasm. The `#[wasm_bindgen_test]`
     /// macro generates invocations of this method.
-    pub fn execute_sync(&self, name: &str, f: impl FnOnce() + 'static) {
-        self.execute(name, future::lazy(|| Ok(f())));
+    pub fn execute_sync(&self, name: &str, f: impl FnOnce() + 'static, meta: TestMeta) {
+        self.execute(name, future::lazy(|| Ok(f())), meta, None);
     }
 
     /// Entry point for an asynchronous in wasm. The
     /// `#[wasm_bindgen_test(async)]` macro generates invocations of this
     /// method.
-    pub fn execute_async<F>(&self, name: &str, f: impl FnOnce() -> F + 'static)
+    pub fn execute_async<F>(&self, name: &str, f: impl FnOnce() -> F + 'static, meta: TestMeta)
         where F: Future<Item = (), Error = JsValue> + 'static,
     {
-        self.execute(name, future::lazy(f))
+        self.execute(name, future::lazy(f), meta, None)
+    }
+
+    /// Registers a function to run before each test, generated by
+    /// `#[wasm_bindgen_test(before_each)]`.
+    pub fn before_each(&self, f: fn()) {
+        self.state.hooks.before_each.borrow_mut().push(f);
+    }
+
+    /// Registers a function to run after each test, generated by
+    /// `#[wasm_bindgen_test(after_each)]`.
+    pub fn after_each(&self, f: fn()) {
+        self.state.hooks.after_each.borrow_mut().push(f);
+    }
+
+    /// Entry point for a benchmark in wasm. The `#[wasm_bindgen_bench]` macro
+    /// generates invocations of this method.
+    pub fn execute_bench(&self, name: &str, f: impl FnOnce(&mut bench::Bencher) + 'static) {
+        let measure = self.state.bench.get();
+        let summary = Rc::new(Cell::new(None));
+        let result = summary.clone();
+        let test = future::lazy(move || {
+            let mut b = bench::Bencher::new(measure);
+            f(&mut b);
+            result.set(b.summary());
+            Ok(())
+        });
+        let meta = TestMeta {
+            should_panic: None,
+            ignore: false,
+            timeout_ms: None,
+        };
+        self.execute(name, test, meta, Some(summary))
     }
 
     fn execute(
         &self,
         name: &str,
         test: impl Future<Item = (), Error = JsValue> + 'static,
+        meta: TestMeta,
+        bench: Option<Rc<Cell<Option<bench::Summary>>>>,
     ) {
         // If our test is filtered out, record that it was filtered and move
-        // on, nothing to do here.
-        let filter = self.state.filter.borrow();
-        if let Some(filter) = &*filter {
-            if !name.contains(filter) {
-                let ignored = self.state.ignored.get();
-                self.state.ignored.set(ignored + 1);
-                return
+        // on, nothing to do here. With `--ignored` only ignored tests are
+        // run, and everything else is filtered out.
+        let ignore = meta.ignore && !self.state.only_ignored.get();
+        if !self.state.matches_filters(name) || (self.state.only_ignored.get() && !meta.ignore) {
+            let filtered_out = self.state.filtered_out.get();
+            self.state.filtered_out.set(filtered_out + 1);
+            return
+        }
+
+        if self.state.list.get() {
+            if bench.is_some() {
+                self.state.formatter.writeln(&format!("{}: bench", name));
+                self.state.listed_benches.set(self.state.listed_benches.get() + 1);
+            } else {
+                self.state.formatter.writeln(&format!("{}: test", name));
+                self.state.listed_tests.set(self.state.listed_tests.get() + 1);
             }
+            return
         }
 
-        // Looks like we've got a test that needs to be executed! Push it onto
-        // the list of remaining tests.
-        let output = Rc::new(RefCell::new(Output::default()));
+        // Tests annotated with `#[ignore]` aren't run at all, and neither are
+        // regular tests when benchmarking.
+        if ignore || (self.state.bench.get() && bench.is_none()) {
+            self.state.ignored.borrow_mut().push(name.to_string());
+            return
+        }
+
+        // Looks like we've got a test that needs to be executed! Run the
+        // before-each hooks ahead of it, which are only looked up once the
+        // test starts as they may be registered after this test. After-each
+        // hooks are run when logging the test's result, as a panicking test
+        // never gets to run anything chained onto it.
+        let hooks = self.state.hooks.clone();
+        let before_each = future::lazy(move || {
+            let hooks = hooks.before_each.borrow().clone();
+            run_hooks(&hooks);
+            Ok(())
+        });
+        let test = before_each.and_then(|()| test);
+
+        // And then push it onto the list of remaining tests.
+        let output = Rc::new(RefCell::new(Output {
+            nocapture: self.state.nocapture.get(),
+            ..Output::default()
+        }));
+        let fixtures = Rc::new(RefCell::new(Vec::new()));
         let future = TestFuture {
             output: output.clone(),
+            fixtures: fixtures.clone(),
             test,
         };
         self.state.remaining.borrow_mut().push(Test {
             name: name.to_string(),
             future: Box::new(future),
             output,
+            should_panic: meta.should_panic,
+            started: 0.0,
+            timeout_ms: meta.timeout_ms.unwrap_or(self.state.timeout_ms.get()),
+            timeout: None,
+            bench,
+            fixtures,
         });
     }
 }
 
+impl Test {
+    fn timed_out(&self) -> bool {
+        match &self.timeout {
+            Some(timeout) => timeout.fired.get(),
+            None => false,
+        }
+    }
+}
+
+/// A `setTimeout` timer which notifies the current task when it fires, used
+/// to wake up the harness when a test times out.
+///
+/// The timer is cleared when dropped.
+struct Timeout {
+    id: JsValue,
+    fired: Rc<Cell<bool>>,
+    _closure: Closure<FnMut()>,
+}
+
+#[wasm_bindgen]
+extern {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(closure: &Closure<FnMut()>, timeout_ms: u32) -> JsValue;
+    #[wasm_bindgen(js_name = clearTimeout)]
+    fn clear_timeout(id: &JsValue);
+}
+
+impl Timeout {
+    fn new(timeout_ms: u32) -> Timeout {
+        let fired = Rc::new(Cell::new(false));
+        let current = task::current();
+        let closure = {
+            let fired = fired.clone();
+            Closure::wrap(Box::new(move || {
+                fired.set(true);
+                current.notify();
+            }) as Box<FnMut()>)
+        };
+        Timeout {
+            id: set_timeout(&closure, timeout_ms),
+            fired,
+            _closure: closure,
+        }
+    }
+}
+
+impl Drop for Timeout {
+    fn drop(&mut self) {
+        clear_timeout(&self.id);
+    }
+}
+
 struct ExecuteTests(Rc<State>);
 
 enum Never {}
@@ -396,6 +753,10 @@ impl Future for ExecuteTests {
         for i in (0..running.len()).rev() {
             let result = match running[i].future.poll() {
                 Ok(Async::Ready(_jsavl)) => Ok(()),
+                Ok(Async::NotReady) if running[i].timed_out() => {
+                    let msg = format!("test timed out after {}ms", running[i].timeout_ms);
+                    Err(Error::new(&msg).into())
+                }
                 Ok(Async::NotReady) => continue,
                 Err(e) => Err(e),
             };
@@ -407,14 +768,18 @@ impl Future for ExecuteTests {
         // we `poll` it once to ensure we'll receive notifications. We only
         // want to schedule up to a maximum amount of work though, so this may
         // not schedule all tests.
-        while running.len() < CONCURRENCY {
+        while running.len() < self.0.concurrency.get() {
             let mut test = match remaining.pop() {
                 Some(test) => test,
                 None => break,
             };
+            test.started = Date::now();
             let result = match test.future.poll() {
                 Ok(Async::Ready(())) => Ok(()),
                 Ok(Async::NotReady) => {
+                    if test.timeout_ms > 0 {
+                        test.timeout = Some(Timeout::new(test.timeout_ms));
+                    }
                     running.push(test);
                     continue
                 }
@@ -433,6 +798,7 @@ impl Future for ExecuteTests {
         // so we shouldn't have any more remaining tests either.
         assert_eq!(remaining.len(), 0);
 
+        self.0.duration.set(Date::now() - self.0.started.get());
         self.0.print_results();
         let all_passed = self.0.failures.borrow().len() == 0;
         Ok(Async::Ready(all_passed))
@@ -440,13 +806,101 @@ impl Future for ExecuteTests {
 }
 
 impl State {
+    /// Returns whether the test `name` passes the test filter and isn't
+    /// skipped.
+    fn matches_filters(&self, name: &str) -> bool {
+        let matches = |pattern: &str| {
+            if self.exact.get() {
+                name == pattern
+            } else {
+                name.contains(pattern)
+            }
+        };
+        if let Some(filter) = &*self.filter.borrow() {
+            if !matches(filter) {
+                return false
+            }
+        }
+        !self.skip.borrow().iter().any(|s| matches(s))
+    }
+
     fn log_test_result(&self, test: Test, result: Result<(), JsValue>) {
-        // Print out information about the test passing or failing
-        self.formatter.log_test(&test.name, &result);
+        // Only a panic in the test itself counts for `#[should_panic]`, a
+        // panicking before-each hook always fails the test.
+        let (panic, hook_panic) = {
+            let output = test.output.borrow();
+            (output.panic.clone(), output.hook_panic.clone())
+        };
+
+        // Regardless of how the test finished, run the after-each hooks and
+        // clean up after it. A panicking hook fails the test.
+        let hooks = self.hooks.after_each.borrow().clone();
+        let hooks_result = CURRENT_OUTPUT.set(&test.output, || {
+            CURRENT_FIXTURES.set(&test.fixtures, || {
+                __wbg_test_invoke(&mut || run_hooks(&hooks))
+            })
+        });
+        fixture::remove_all(&mut test.fixtures.borrow_mut());
+
+        // Tests annotated with `#[should_panic]` flip their result, and
+        // additionally check the panic message if one is expected. Only an
+        // actual panic counts, not a test which merely failed with an error.
+        let result = match (test.should_panic, result, panic) {
+            (None, result, _) => result,
+            (Some(_), Ok(()), _) | (Some(_), Err(_), None) => {
+                Err(Error::new("test did not panic as expected").into())
+            }
+            (Some(None), Err(_), Some(_)) => Ok(()),
+            (Some(Some(expected)), Err(_), Some(msg)) => {
+                if msg.contains(expected) {
+                    Ok(())
+                } else {
+                    Err(Error::new(&format!(
+                        "panic did not include expected string '{}'\n\
+                         panic message: {}",
+                        expected, msg,
+                    )).into())
+                }
+            }
+        };
+
+        let result = match hook_panic {
+            Some(msg) => Err(Error::new(&format!("before-each hook panicked: {}", msg)).into()),
+            None => result,
+        };
+        let result = result.and(hooks_result);
+
+        // Print out information about the test passing or failing, or the
+        // measurements of a benchmark.
+        let summary = test.bench.as_ref().and_then(|b| b.get());
+        match (&result, summary) {
+            (Ok(()), Some(summary)) => {
+                self.formatter.writeln(&format!(
+                    "test {} ... bench: {:>11} ns/iter (+/- {})",
+                    test.name,
+                    bench::fmt_thousands(summary.median),
+                    bench::fmt_thousands(summary.deviation),
+                ));
+            }
+            _ => self.formatter.log_test(&test.name, &result),
+        }
+
+        {
+            let output = test.output.borrow();
+            self.reports.borrow_mut().push(report::TestReport {
+                name: test.name.clone(),
+                status: if result.is_ok() { report::Status::Ok } else { report::Status::Failed },
+                duration_ms: Date::now() - test.started,
+                log: output.log.clone(),
+                error: output.error.clone(),
+                failure: result.as_ref().err().map(|e| self.formatter.stringify_error(e)),
+            });
+        }
 
         // Save off the test for later processing when we print the final
         // results.
         match result {
+            Ok(()) if summary.is_some() => self.measured.set(self.measured.get() + 1),
             Ok(()) => self.succeeded.set(self.succeeded.get() + 1),
             Err(e) => self.failures.borrow_mut().push((test, e)),
         }
@@ -469,11 +923,15 @@ impl State {
             "test result: {}. \
              {} passed; \
              {} failed; \
-             {} ignored\n",
+             {} ignored; \
+             {} measured; \
+             {} filtered out\n",
             if failures.len() == 0 { "ok" } else { "FAILED" },
             self.succeeded.get(),
             failures.len(),
-            self.ignored.get(),
+            self.ignored.borrow().len(),
+            self.measured.get(),
+            self.filtered_out.get(),
         ));
     }
 
@@ -528,6 +986,7 @@ impl State {
 /// perfect code on the first try, right? *sobs*
 struct TestFuture<F> {
     output: Rc<RefCell<Output>>,
+    fixtures: Rc<RefCell<Vec<JsValue>>>,
     test: F,
 }
 
@@ -544,8 +1003,11 @@ impl<F: Future<Error = JsValue>> Future for TestFuture<F> {
     fn poll(&mut self) -> Poll<F::Item, F::Error> {
         let test = &mut self.test;
         let mut future_output = None;
+        let fixtures = &self.fixtures;
         CURRENT_OUTPUT.set(&self.output, || {
-            __wbg_test_invoke(&mut || future_output = Some(test.poll()))
+            CURRENT_FIXTURES.set(fixtures, || {
+                __wbg_test_invoke(&mut || future_output = Some(test.poll()))
+            })
         })?;
         future_output.unwrap()
     }
@@ -0,0 +1,183 @@
+//! Machine-readable reports of a test run, for consumption by CI systems.
+//!
+//! Two formats are supported: a stream of newline-delimited JSON events in the
+//! same shape as libtest's `--format json`, and JUnit XML.
+
+use std::fmt::Write;
+
+/// The outcome of one test which was executed or ignored.
+pub struct TestReport {
+    /// Full path of the test, including the module it's defined in.
+    pub name: String,
+    /// Whether the test passed, failed, or was ignored.
+    pub status: Status,
+    /// How long the test took to execute, in milliseconds.
+    pub duration_ms: f64,
+    /// Captured `console.log` output.
+    pub log: String,
+    /// Captured `console.error` output.
+    pub error: String,
+    /// The stringified exception the test failed with, if it failed.
+    pub failure: Option<String>,
+}
+
+/// The outcome of a test.
+#[derive(PartialEq)]
+pub enum Status {
+    Ok,
+    Failed,
+    Ignored,
+}
+
+/// Summary of an entire test run.
+pub struct Summary<'a> {
+    pub tests: &'a [TestReport],
+    pub filtered_out: usize,
+    pub duration_ms: f64,
+}
+
+impl<'a> Summary<'a> {
+    fn count(&self, status: Status) -> usize {
+        self.tests.iter().filter(|t| t.status == status).count()
+    }
+
+    /// Renders libtest-style JSON events, one per line.
+    pub fn json(&self) -> String {
+        let mut dst = String::new();
+        writeln!(
+            dst,
+            r#"{{ "type": "suite", "event": "started", "test_count": {} }}"#,
+            self.tests.len(),
+        ).unwrap();
+        for test in self.tests.iter() {
+            let name = json_string(&test.name);
+            writeln!(dst, r#"{{ "type": "test", "event": "started", "name": {} }}"#, name)
+                .unwrap();
+            let event = match test.status {
+                Status::Ok => "ok",
+                Status::Failed => "failed",
+                Status::Ignored => "ignored",
+            };
+            write!(
+                dst,
+                r#"{{ "type": "test", "name": {}, "event": "{}""#,
+                name, event,
+            ).unwrap();
+            if test.status != Status::Ignored {
+                write!(dst, r#", "exec_time": {}"#, test.duration_ms / 1000.0).unwrap();
+            }
+            if test.log.len() > 0 || test.error.len() > 0 {
+                let stdout = format!("{}{}", test.log, test.error);
+                write!(dst, r#", "stdout": {}"#, json_string(&stdout)).unwrap();
+            }
+            if let Some(failure) = &test.failure {
+                write!(dst, r#", "message": {}"#, json_string(failure)).unwrap();
+            }
+            dst.push_str(" }\n");
+        }
+        let failed = self.count(Status::Failed);
+        writeln!(
+            dst,
+            r#"{{ "type": "suite", "event": "{}", "passed": {}, "failed": {}, "ignored": {}, "measured": 0, "filtered_out": {}, "exec_time": {} }}"#,
+            if failed == 0 { "ok" } else { "failed" },
+            self.count(Status::Ok),
+            failed,
+            self.count(Status::Ignored),
+            self.filtered_out,
+            self.duration_ms / 1000.0,
+        ).unwrap();
+        dst
+    }
+
+    /// Renders a JUnit XML report with one `testsuite`.
+    pub fn junit(&self) -> String {
+        let mut dst = String::new();
+        dst.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        dst.push_str("<testsuites>\n");
+        writeln!(
+            dst,
+            "  <testsuite name=\"wasm-bindgen-test\" tests=\"{}\" failures=\"{}\" \
+             skipped=\"{}\" errors=\"0\" time=\"{}\">",
+            self.tests.len(),
+            self.count(Status::Failed),
+            self.count(Status::Ignored),
+            self.duration_ms / 1000.0,
+        ).unwrap();
+        for test in self.tests.iter() {
+            // JUnit expects a class name, so split off the test's module path
+            // to use for that.
+            let (classname, name) = match test.name.rfind("::") {
+                Some(i) => (&test.name[..i], &test.name[i + 2..]),
+                None => ("", &test.name[..]),
+            };
+            write!(
+                dst,
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\">\n",
+                xml_escape(classname),
+                xml_escape(name),
+                test.duration_ms / 1000.0,
+            ).unwrap();
+            match test.status {
+                Status::Ok => {}
+                Status::Ignored => dst.push_str("      <skipped/>\n"),
+                Status::Failed => {
+                    let failure = test.failure.as_ref().map(|s| &s[..]).unwrap_or("");
+                    let message = failure.lines().next().unwrap_or("");
+                    writeln!(
+                        dst,
+                        "      <failure message=\"{}\">{}</failure>",
+                        xml_escape(message),
+                        xml_escape(failure),
+                    ).unwrap();
+                }
+            }
+            if test.log.len() > 0 {
+                writeln!(dst, "      <system-out>{}</system-out>", xml_escape(&test.log))
+                    .unwrap();
+            }
+            if test.error.len() > 0 {
+                writeln!(dst, "      <system-err>{}</system-err>", xml_escape(&test.error))
+                    .unwrap();
+            }
+            dst.push_str("    </testcase>\n");
+        }
+        dst.push_str("  </testsuite>\n");
+        dst.push_str("</testsuites>\n");
+        dst
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut dst = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => dst.push_str("\\\""),
+            '\\' => dst.push_str("\\\\"),
+            '\n' => dst.push_str("\\n"),
+            '\r' => dst.push_str("\\r"),
+            '\t' => dst.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(dst, "\\u{:04x}", c as u32).unwrap(),
+            c => dst.push(c),
+        }
+    }
+    dst.push_str("\"");
+    dst
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut dst = String::new();
+    for c in s.chars() {
+        match c {
+            '&' => dst.push_str("&amp;"),
+            '<' => dst.push_str("&lt;"),
+            '>' => dst.push_str("&gt;"),
+            '"' => dst.push_str("&quot;"),
+            '\'' => dst.push_str("&apos;"),
+            // Control characters other than whitespace aren't allowed in XML
+            // at all, so drop them.
+            c if (c as u32) < 0x20 && c != '\n' && c != '\r' && c != '\t' => {}
+            c => dst.push(c),
+        }
+    }
+    dst
+}
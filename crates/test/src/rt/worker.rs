@@ -0,0 +1,42 @@
+//! Support for printing status information of a test suite in a web worker.
+//!
+//! Workers have no DOM to render output into, so all output is sent to the
+//! page which spawned the worker and rendered there instead.
+
+use wasm_bindgen::prelude::*;
+
+use super::browser;
+
+/// Implementation of `Formatter` for web workers.
+///
+/// Routes all output through `__wbg_test_output_writeln`, which the worker
+/// script generated by the test runner defines to post it to the page.
+pub struct Worker {}
+
+#[wasm_bindgen]
+extern {
+    fn __wbg_test_output_writeln(line: &str);
+}
+
+impl Worker {
+    /// Creates a new instance of `Worker`, assuming that we're executing in a
+    /// worker spawned by the test runner.
+    pub fn new() -> Worker {
+        Worker {}
+    }
+}
+
+impl super::Formatter for Worker {
+    fn writeln(&self, line: &str) {
+        __wbg_test_output_writeln(line);
+    }
+
+    fn log_test(&self, name: &str, result: &Result<(), JsValue>) {
+        let s = if result.is_ok() { "ok" } else { "FAIL" };
+        self.writeln(&format!("test {} ... {}", name, s));
+    }
+
+    fn stringify_error(&self, err: &JsValue) -> String {
+        browser::stringify_error(err)
+    }
+}
@@ -1,33 +1,14 @@
 #![feature(use_extern_macros)]
 
+extern crate js_sys;
 extern crate wasm_bindgen;
 
+use js_sys::{Function, Object, Reflect, WebAssembly};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 
-// Like with the `dom` example this block will eventually be auto-generated, but
-// for now we can write it by hand to get by!
 #[wasm_bindgen]
 extern "C" {
-    type Module;
-    #[wasm_bindgen(constructor, js_namespace = WebAssembly)]
-    fn new(data: &[u8]) -> Module;
-
-    type Instance;
-    #[wasm_bindgen(constructor, js_namespace = WebAssembly)]
-    fn new(module: Module) -> Instance;
-    #[wasm_bindgen(method, getter, js_namespace = WebAssembly)]
-    fn exports(this: &Instance) -> Exports;
-
-    type Exports;
-    #[wasm_bindgen(method, structural)]
-    fn add(this: &Exports, a: u32, b: u32) -> u32;
-    #[wasm_bindgen(method, getter, structural)]
-    fn memory(this: &Exports) -> Memory;
-
-    type Memory;
-    #[wasm_bindgen(method, js_namespace = WebAssembly)]
-    fn grow(this: &Memory, amt: u32) -> u32;
-
     #[wasm_bindgen(js_namespace = console)]
     fn log(a: &str);
 }
@@ -41,14 +22,22 @@ const WASM: &[u8] = include_bytes!("add.wasm");
 #[wasm_bindgen]
 pub fn run() {
     println!("instantiating a new wasm module directly");
-    let a = Module::new(WASM);
-    let b = Instance::new(a);
+
+    let a = WebAssembly::Module::new_with_bytes(WASM).unwrap();
+    let b = WebAssembly::Instance::new(&a, &Object::new()).unwrap();
     let c = b.exports();
 
-    println!("1 + 2 = {}", c.add(1, 2));
-    let mem = c.memory();
-    println!("created module has {} pages of memory", mem.grow(0));
+    let add = Reflect::get(c.as_ref(), &"add".into())
+        .dyn_into::<Function>()
+        .expect("add export wasn't a function");
+    let three = add.call2(&JsValue::undefined(), &1.into(), &2.into()).unwrap();
+    println!("1 + 2 = {}", three.as_f64().unwrap());
+
+    let mem = Reflect::get(c.as_ref(), &"memory".into())
+        .dyn_into::<WebAssembly::Memory>()
+        .expect("memory export wasn't a `WebAssembly.Memory`");
+    println!("created module has {} pages of memory", mem.grow(0).unwrap());
     println!("giving the module 4 more pages of memory");
-    mem.grow(4);
-    println!("now the module has {} pages of memory", mem.grow(0));
+    mem.grow(4).unwrap();
+    println!("now the module has {} pages of memory", mem.grow(0).unwrap());
 }
@@ -39,6 +39,7 @@ tys! {
     RUST_STRUCT
     CHAR
     OPTIONAL
+    PROMISE
 }
 
 pub fn inform(a: u32) {
@@ -0,0 +1,12 @@
+use wasm_bindgen_test::*;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(inline_js = "exports.inline_js_add = (a, b) => a + b;")]
+extern {
+    fn inline_js_add(a: u32, b: u32) -> u32;
+}
+
+#[wasm_bindgen_test]
+fn works() {
+    assert_eq!(inline_js_add(1, 2), 3);
+}
@@ -0,0 +1 @@
+exports.local_module_add = (a, b) => a + b;
@@ -0,0 +1,12 @@
+use wasm_bindgen_test::*;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/tests/wasm/local_module.js")]
+extern {
+    fn local_module_add(a: u32, b: u32) -> u32;
+}
+
+#[wasm_bindgen_test]
+fn works() {
+    assert_eq!(local_module_add(1, 2), 3);
+}
@@ -20,8 +20,10 @@ pub mod duplicates;
 pub mod enums;
 pub mod import_class;
 pub mod imports;
+pub mod inline_js;
 pub mod js_objects;
 pub mod jscast;
+pub mod local_module;
 pub mod math;
 pub mod node;
 pub mod option;